@@ -49,8 +49,13 @@ func (s *Server) Start() error {
 	mux.HandleFunc("/api/models/{id}/verify", s.VerifyModelHandler)
 	mux.HandleFunc("/api/providers", s.ProvidersHandler)
 
+	addr := ":8080"
+	if s.config != nil && s.config.API.Port != "" {
+		addr = ":" + s.config.API.Port
+	}
+
 	s.server = &http.Server{
-		Addr:    ":8080",
+		Addr:    addr,
 		Handler: mux,
 	}
 
@@ -156,6 +156,7 @@ func runServer(port string) error {
 			port = "8080"
 		}
 	}
+	cfg.API.Port = port
 
 	return server.Start()
 }
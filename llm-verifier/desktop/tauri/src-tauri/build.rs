@@ -0,0 +1,61 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    embed_build_info();
+    tauri_build::build()
+}
+
+/// Exposes compiler, profile and git metadata to `system::BuildInfo` through
+/// `env!`.
+fn embed_build_info() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = output(Command::new(rustc).arg("--version"));
+    let git_commit = output(Command::new("git").args(["rev-parse", "--short=12", "HEAD"]));
+    let git_dirty =
+        output(Command::new("git").args(["status", "--porcelain", "--untracked-files=no"]))
+            .map(|status| !status.is_empty());
+
+    println!(
+        "cargo:rustc-env=BUILD_RUSTC_VERSION={}",
+        rustc_version.unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=BUILD_GIT_COMMIT={}",
+        match (git_commit, git_dirty) {
+            (Some(commit), Some(true)) => format!("{}-dirty", commit),
+            (Some(commit), _) => commit,
+            (None, _) => String::new(),
+        }
+    );
+    println!(
+        "cargo:rustc-env=BUILD_PROFILE={}",
+        env::var("PROFILE").unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=BUILD_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+
+    // Rebuild when the checked-out commit moves.
+    if let Some(head) = output(Command::new("git").args(["rev-parse", "--git-path", "HEAD"])) {
+        println!("cargo:rerun-if-changed={}", head);
+    }
+    if let Some(reference) = output(Command::new("git").args(["symbolic-ref", "-q", "HEAD"])) {
+        if let Some(path) =
+            output(Command::new("git").args(["rev-parse", "--git-path", &reference]))
+        {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// Trimmed stdout of a successful command.
+fn output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
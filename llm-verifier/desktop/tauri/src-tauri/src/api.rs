@@ -0,0 +1,206 @@
+//! Commands talking to the `llm-verifier` REST API through the
+//! `llm-verifier-client` SDK, routed through the active connection profile
+//! and authenticated with the login session, if any.
+
+use std::time::Duration;
+
+use llm_verifier_client::types::{
+    Model, ModelDetails, ModelQuery, Provider, ProviderQuery, Verification,
+};
+use llm_verifier_client::{Client, ClientBuilder};
+use reqwest::StatusCode;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use tauri::{AppHandle, Manager, State};
+
+use crate::backend::profile::Profiles;
+use crate::backend::{BackendError, BackendProcess};
+use crate::cache::{CachedList, ResponseCache};
+use crate::session::{BackendScope, EndReason, Session};
+use crate::vault::Vault;
+use crate::workspace::Workspaces;
+
+/// Timeout of a single API request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors surfaced by the API commands.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error(transparent)]
+    Client(#[from] llm_verifier_client::Error),
+    #[error("Cached response is unreadable: {0}")]
+    Cache(String),
+    #[error("Login failed: {0}")]
+    Login(String),
+}
+
+impl ApiError {
+    fn kind(&self) -> &'static str {
+        use llm_verifier_client::Error;
+
+        match self {
+            Self::Backend(_) => "backend",
+            Self::Client(Error::InvalidBaseUrl(_)) => "invalid_base_url",
+            Self::Client(Error::Transport { .. }) => "transport",
+            Self::Client(Error::Status { status, .. }) if *status == StatusCode::UNAUTHORIZED => {
+                "unauthorized"
+            }
+            Self::Client(Error::Status { .. }) => "status",
+            Self::Client(Error::Decode { .. }) => "decode",
+            Self::Client(Error::TokenExpired(_)) => "token_expired",
+            Self::Client(Error::LoginUnsupported) => "login_unsupported",
+            Self::Cache(_) => "cache",
+            Self::Login(_) => "login",
+        }
+    }
+}
+
+// Same `{ kind, message, details }` shape as `BackendError`.
+impl Serialize for ApiError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::Backend(e) => map.serialize_entry("details", e)?,
+            Self::Client(llm_verifier_client::Error::Status { status, body, .. }) => map
+                .serialize_entry(
+                    "details",
+                    &serde_json::json!({ "status": status.as_u16(), "body": body }),
+                )?,
+            _ => map.serialize_entry("details", &())?,
+        }
+        map.end()
+    }
+}
+
+/// Managed state sharing one HTTP connection pool between commands.
+pub struct Api(reqwest::Client);
+
+impl Default for Api {
+    fn default() -> Self {
+        Self(
+            reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("failed to build HTTP client"),
+        )
+    }
+}
+
+impl Api {
+    /// Client for the backend selected by the active connection profile,
+    /// carrying the session token when logged in to that backend.
+    pub async fn client(&self, app: &AppHandle) -> Result<Client, ApiError> {
+        let (mut builder, scope) = self.connect(app).await?;
+        if let Some(token) = app.state::<Session>().token(app, &scope).await {
+            builder = builder.token(token);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Like [`Api::client`] but without the session token, for logging in.
+    pub async fn anonymous_client(
+        &self,
+        app: &AppHandle,
+    ) -> Result<(Client, BackendScope), ApiError> {
+        let (builder, scope) = self.connect(app).await?;
+        Ok((builder.build()?, scope))
+    }
+
+    async fn connect(&self, app: &AppHandle) -> Result<(ClientBuilder, BackendScope), ApiError> {
+        let backend = app.state::<BackendProcess>();
+        let connection = app
+            .state::<Profiles>()
+            .connection(&backend, &app.state::<Vault>())
+            .await?;
+        let scope = BackendScope::current(app)
+            .await
+            .ok_or(BackendError::NotRunning)?;
+        Ok((connection.builder(self.0.clone()), scope))
+    }
+}
+
+/// Ends the session when the backend rejects its token, so the webview asks
+/// for a new login. The server has no refresh endpoint to try first.
+pub async fn settle<T>(
+    app: &AppHandle,
+    result: llm_verifier_client::Result<T>,
+) -> Result<T, ApiError> {
+    match result {
+        Err(e) if e.status() == Some(StatusCode::UNAUTHORIZED) => {
+            app.state::<Session>().end(app, EndReason::Rejected).await;
+            Err(e.into())
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Cache key of a list: workspaces and profiles have separate caches, as do
+/// filters.
+async fn cache_key<Q: Serialize>(app: &AppHandle, resource: &str, query: &Q) -> String {
+    let workspace = app.state::<Workspaces>().active_name().await;
+    let profile = app.state::<Profiles>().active_name().await;
+    let query = serde_json::to_string(query).unwrap_or_default();
+    format!("{}/{}/{}?{}", workspace, profile, resource, query)
+}
+
+#[tauri::command]
+pub async fn list_models(
+    app: AppHandle,
+    api: State<'_, Api>,
+    cache: State<'_, ResponseCache>,
+    filter: Option<ModelQuery>,
+) -> Result<CachedList<Model>, ApiError> {
+    let query = filter.unwrap_or_default();
+    let key = cache_key(&app, "models", &query).await;
+    let api = api.inner();
+    cache
+        .list(key, |validators| async move {
+            let client = api.client(&app).await?;
+            let result = client.list_models_if_modified(&query, &validators).await;
+            settle(&app, result).await
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_model(
+    app: AppHandle,
+    api: State<'_, Api>,
+    id: i64,
+) -> Result<ModelDetails, ApiError> {
+    let client = api.client(&app).await?;
+    settle(&app, client.get_model(id).await).await
+}
+
+#[tauri::command]
+pub async fn verify_model(
+    app: AppHandle,
+    api: State<'_, Api>,
+    id: i64,
+) -> Result<Verification, ApiError> {
+    let client = api.client(&app).await?;
+    settle(&app, client.verify_model(id).await).await
+}
+
+#[tauri::command]
+pub async fn list_providers(
+    app: AppHandle,
+    api: State<'_, Api>,
+    cache: State<'_, ResponseCache>,
+    filter: Option<ProviderQuery>,
+) -> Result<CachedList<Provider>, ApiError> {
+    let query = filter.unwrap_or_default();
+    let key = cache_key(&app, "providers", &query).await;
+    let api = api.inner();
+    cache
+        .list(key, |validators| async move {
+            let client = api.client(&app).await?;
+            let result = client.list_providers_if_modified(&query, &validators).await;
+            settle(&app, result).await
+        })
+        .await
+}
@@ -0,0 +1,656 @@
+//! Supervision of the `llm-verifier` backend process spawned by the desktop app.
+
+pub mod binary;
+pub mod config_file;
+pub mod health;
+pub mod launch;
+pub mod lockfile;
+pub mod logs;
+pub mod port;
+pub mod profile;
+pub mod watchdog;
+pub mod yaml_edit;
+
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+
+use binary::Rejected;
+use health::{HealthSnapshot, ReadinessFailure};
+use lockfile::Lockfile;
+use logs::{BackendLogs, LogStream};
+use port::Endpoint;
+use profile::ProfileKind;
+
+use crate::vault::VaultError;
+
+/// How long the backend gets to exit after SIGTERM before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of trailing stderr lines kept for crash reports.
+const STDERR_TAIL_LINES: usize = 50;
+
+/// Errors surfaced by the backend lifecycle commands.
+#[derive(Debug, thiserror::Error)]
+pub enum BackendError {
+    #[error("Backend is already running (pid {0})")]
+    AlreadyRunning(u32),
+    #[error("Backend is not running")]
+    NotRunning,
+    #[error("Backend (pid {0}) is still shutting down")]
+    Stopping(u32),
+    #[error("Backend binary not found ({})", describe_rejected(.0))]
+    NotFound(Vec<Rejected>),
+    #[error("Failed to start backend: {0}")]
+    Spawn(#[source] std::io::Error),
+    #[error("Failed to stop backend: {0}")]
+    Stop(#[source] std::io::Error),
+    #[error("Invalid backend settings in desktop config: {0}")]
+    InvalidConfig(String),
+    #[error("Failed to probe backend binary: {0}")]
+    Probe(String),
+    #[error(
+        "Backend binary does not support the `{subcommand}` command (available: {})",
+        available.join(", ")
+    )]
+    UnsupportedMode {
+        subcommand: String,
+        available: Vec<String>,
+    },
+    #[error("Port {0} is already in use")]
+    PortInUse(u16),
+    #[error("Failed to allocate a backend port: {0}")]
+    Port(#[source] std::io::Error),
+    #[error("Backend did not become ready: {}", .0.reason)]
+    NotReady(Box<ReadinessFailure>),
+    #[error("Failed to adopt backend: {0}")]
+    Adopt(String),
+    #[error("The active connection profile uses the remote backend at {0}")]
+    RemoteProfile(String),
+    #[error("Cannot pass vault secrets to the backend: {0}")]
+    Vault(#[source] VaultError),
+}
+
+impl BackendError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::AlreadyRunning(_) => "already_running",
+            Self::NotRunning => "not_running",
+            Self::Stopping(_) => "stopping",
+            Self::NotFound(_) => "not_found",
+            Self::Spawn(_) => "spawn",
+            Self::Stop(_) => "stop",
+            Self::InvalidConfig(_) => "invalid_config",
+            Self::Probe(_) => "probe",
+            Self::UnsupportedMode { .. } => "unsupported_mode",
+            Self::PortInUse(_) => "port_in_use",
+            Self::Port(_) => "port",
+            Self::NotReady(_) => "not_ready",
+            Self::Adopt(_) => "adopt",
+            Self::RemoteProfile(_) => "remote_profile",
+            // The webview asks for the passphrase and retries.
+            Self::Vault(VaultError::Locked) => "vault_locked",
+            Self::Vault(_) => "vault",
+        }
+    }
+}
+
+// Commands hand errors to the webview as `{ kind, message, details }`.
+impl Serialize for BackendError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::NotFound(rejected) => map.serialize_entry("details", rejected)?,
+            Self::NotReady(failure) => map.serialize_entry("details", failure)?,
+            Self::Vault(e) => map.serialize_entry("details", e)?,
+            _ => map.serialize_entry("details", &())?,
+        }
+        map.end()
+    }
+}
+
+fn describe_rejected(rejected: &[Rejected]) -> String {
+    rejected
+        .iter()
+        .map(|r| match &r.path {
+            Some(path) => format!("{:?}: {} {}", r.source, path.display(), r.reason),
+            None => format!("{:?}: {}", r.source, r.reason),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Program, arguments and environment used to (re)launch the backend, and
+/// the endpoint it will listen on.
+#[derive(Clone)]
+pub struct LaunchSpec {
+    pub program: PathBuf,
+    pub args: Vec<OsString>,
+    pub env: Vec<(String, String)>,
+    pub endpoint: Endpoint,
+}
+
+// The environment carries vault secrets, so only its names are printed.
+impl fmt::Debug for LaunchSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LaunchSpec")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .field("env", &self.env.iter().map(|(k, _)| k).collect::<Vec<_>>())
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+impl LaunchSpec {
+    pub fn new<I, S>(program: impl Into<PathBuf>, args: I, endpoint: Endpoint) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            env: Vec::new(),
+            endpoint,
+        }
+    }
+}
+
+/// How the backend process ended.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitInfo {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub exited_at: DateTime<Utc>,
+}
+
+impl ExitInfo {
+    /// Exit of a process we could not wait on, such as an adopted backend.
+    fn unknown() -> Self {
+        Self {
+            code: None,
+            signal: None,
+            exited_at: Utc::now(),
+        }
+    }
+
+    fn from_status(status: ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            code: status.code(),
+            signal,
+            exited_at: Utc::now(),
+        }
+    }
+}
+
+/// An exit the user did not ask for, waiting to be handled by the watchdog.
+#[derive(Debug, Clone)]
+pub struct Crash {
+    pub exit: ExitInfo,
+    pub stderr: Vec<String>,
+    pub uptime: Duration,
+    generation: u64,
+}
+
+/// Snapshot returned by `get_backend_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub profile: ProfileKind,
+    /// Whether a managed backend process is alive; always `false` for remote profiles.
+    pub running: bool,
+    /// A stop is waiting for the backend to exit; starting is refused meanwhile.
+    pub stopping: bool,
+    /// `false` for a backend adopted from a previous session: its output went
+    /// to that session, so `get_backend_logs` has nothing from it.
+    pub logs_available: bool,
+    pub pid: Option<u32>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub last_exit: Option<ExitInfo>,
+    /// Where the current (or last) backend listens; `None` before the first start.
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub base_url: Option<String>,
+    /// Latest health check of the running backend.
+    pub health: Option<HealthSnapshot>,
+}
+
+impl BackendStatus {
+    pub fn remote(base_url: String, health: Option<HealthSnapshot>) -> Self {
+        Self {
+            profile: ProfileKind::Remote,
+            running: false,
+            stopping: false,
+            logs_available: false,
+            pid: None,
+            started_at: None,
+            last_exit: None,
+            host: None,
+            port: None,
+            base_url: Some(base_url),
+            health,
+        }
+    }
+}
+
+struct ManagedChild {
+    /// `None` for a backend adopted from a previous session, which can be
+    /// signalled but not waited on.
+    child: Option<Child>,
+    pid: u32,
+    started_at: DateTime<Utc>,
+    /// First log sequence number produced by this process.
+    first_log_seq: u64,
+}
+
+struct Supervisor {
+    logs: BackendLogs,
+    current: Option<ManagedChild>,
+    /// Pid of a backend that `stop` has taken over but that has not exited yet.
+    stopping: Option<u32>,
+    last_exit: Option<ExitInfo>,
+    launch: Option<LaunchSpec>,
+    crash: Option<Crash>,
+    health: Option<HealthSnapshot>,
+    /// Where the running backend is recorded; see [`lockfile`].
+    lockfile: Option<PathBuf>,
+    /// Bumped on every explicit start/stop so stale crash handling can be dropped.
+    generation: u64,
+}
+
+impl Supervisor {
+    /// Drops the current child if it has exited on its own, recording how it ended.
+    fn reap(&mut self) {
+        let managed = match self.current.as_mut() {
+            Some(managed) => managed,
+            None => return,
+        };
+        let child = match managed.child.as_mut() {
+            Some(child) => child,
+            None => {
+                // Adopted backends are not ours to restart; just note that they are gone.
+                if !lockfile::is_alive(managed.pid) {
+                    self.last_exit = Some(ExitInfo::unknown());
+                    self.clear_current();
+                }
+                return;
+            }
+        };
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let exit = ExitInfo::from_status(status);
+                let uptime = (exit.exited_at - managed.started_at)
+                    .to_std()
+                    .unwrap_or_default();
+                let stderr = self.logs.tail(
+                    Some(LogStream::Stderr),
+                    managed.first_log_seq,
+                    STDERR_TAIL_LINES,
+                );
+                self.crash = Some(Crash {
+                    exit: exit.clone(),
+                    stderr,
+                    uptime,
+                    generation: self.generation,
+                });
+                self.last_exit = Some(exit);
+                self.clear_current();
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to poll backend process: {}", e),
+        }
+    }
+
+    fn clear_current(&mut self) {
+        self.current = None;
+        self.health = None;
+        self.remove_lockfile();
+    }
+
+    fn remove_lockfile(&self) {
+        if let Some(path) = &self.lockfile {
+            lockfile::remove(path);
+        }
+    }
+
+    /// Refuses to start or adopt a backend while one is running or stopping.
+    fn ensure_idle(&mut self) -> Result<(), BackendError> {
+        self.reap();
+        if let Some(managed) = &self.current {
+            return Err(BackendError::AlreadyRunning(managed.pid));
+        }
+        match self.stopping {
+            Some(pid) => Err(BackendError::Stopping(pid)),
+            None => Ok(()),
+        }
+    }
+
+    fn write_lockfile(&self) {
+        if let (Some(path), Some(managed), Some(spec)) =
+            (&self.lockfile, &self.current, &self.launch)
+        {
+            lockfile::write(
+                path,
+                &Lockfile {
+                    pid: managed.pid,
+                    host: spec.endpoint.host.clone(),
+                    port: spec.endpoint.port,
+                    program: spec.program.clone(),
+                    started_at: managed.started_at,
+                },
+            );
+        }
+    }
+
+    fn spawn(&mut self, spec: &LaunchSpec) -> Result<u32, BackendError> {
+        let mut child = Command::new(&spec.program)
+            .args(&spec.args)
+            .envs(spec.env.iter().map(|(k, v)| (k, v)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(BackendError::Spawn)?;
+        let pid = child.id().unwrap_or_default();
+
+        // Drain both pipes so the backend never blocks on a full buffer.
+        let first_log_seq = self.logs.next_seq();
+        if let Some(stdout) = child.stdout.take() {
+            self.logs.capture(LogStream::Stdout, stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.logs.capture(LogStream::Stderr, stderr);
+        }
+
+        self.current = Some(ManagedChild {
+            child: Some(child),
+            pid,
+            started_at: Utc::now(),
+            first_log_seq,
+        });
+        self.launch = Some(spec.clone());
+        self.health = Some(HealthSnapshot::starting());
+        self.write_lockfile();
+        Ok(pid)
+    }
+}
+
+/// Managed state owning the spawned backend process.
+pub struct BackendProcess(Mutex<Supervisor>);
+
+impl BackendProcess {
+    pub fn new(logs: BackendLogs) -> Self {
+        Self(Mutex::new(Supervisor {
+            logs,
+            current: None,
+            stopping: None,
+            last_exit: None,
+            launch: None,
+            crash: None,
+            health: None,
+            lockfile: None,
+            generation: 0,
+        }))
+    }
+
+    /// Spawns the backend unless one is already running or stopping.
+    pub async fn start(&self, spec: LaunchSpec) -> Result<u32, BackendError> {
+        let mut supervisor = self.0.lock().await;
+        supervisor.ensure_idle()?;
+
+        supervisor.generation += 1;
+        supervisor.crash = None;
+        supervisor.spawn(&spec)
+    }
+
+    /// Asks the backend to shut down, killing it if it does not exit in time.
+    /// The supervisor is not locked while waiting for the exit, but stays in
+    /// the stopping state so nothing else is started until the process is
+    /// gone; only then is the lockfile removed.
+    pub async fn stop(&self) -> Result<ExitInfo, BackendError> {
+        let managed = {
+            let mut supervisor = self.0.lock().await;
+            supervisor.reap();
+            if let Some(pid) = supervisor.stopping {
+                return Err(BackendError::Stopping(pid));
+            }
+            let managed = supervisor.current.take().ok_or(BackendError::NotRunning)?;
+            supervisor.generation += 1;
+            supervisor.crash = None;
+            supervisor.health = None;
+            supervisor.stopping = Some(managed.pid);
+            managed
+        };
+
+        let pid = managed.pid;
+        let result = match managed.child {
+            Some(child) => terminate(child).await.map(ExitInfo::from_status),
+            None => terminate_pid(pid).await.map(|_| ExitInfo::unknown()),
+        };
+
+        let mut supervisor = self.0.lock().await;
+        supervisor.stopping = None;
+        match result {
+            Ok(exit) => {
+                supervisor.remove_lockfile();
+                supervisor.last_exit = Some(exit.clone());
+                Ok(exit)
+            }
+            Err(e) => {
+                // Keep the lockfile while the process lives so the next
+                // session still finds it.
+                if !lockfile::is_alive(pid) {
+                    supervisor.remove_lockfile();
+                }
+                Err(BackendError::Stop(e))
+            }
+        }
+    }
+
+    /// Records the running backend in `path` from now on.
+    pub async fn attach_lockfile(&self, path: PathBuf) {
+        let mut supervisor = self.0.lock().await;
+        supervisor.lockfile = Some(path);
+        supervisor.write_lockfile();
+    }
+
+    /// Takes over a backend left running by a previous session.
+    pub async fn adopt(&self, lock: &Lockfile) -> Result<(), BackendError> {
+        let mut supervisor = self.0.lock().await;
+        supervisor.ensure_idle()?;
+
+        supervisor.generation += 1;
+        supervisor.crash = None;
+        supervisor.current = Some(ManagedChild {
+            child: None,
+            pid: lock.pid,
+            started_at: lock.started_at,
+            first_log_seq: supervisor.logs.next_seq(),
+        });
+        supervisor.launch = Some(LaunchSpec::new(
+            &lock.program,
+            Vec::<OsString>::new(),
+            lock.endpoint(),
+        ));
+        supervisor.health = Some(HealthSnapshot::starting());
+        Ok(())
+    }
+
+    /// Reports whether the backend is alive, or how it last exited.
+    pub async fn status(&self) -> BackendStatus {
+        let mut supervisor = self.0.lock().await;
+        supervisor.reap();
+        let current = supervisor.current.as_ref();
+        let endpoint = supervisor.launch.as_ref().map(|spec| &spec.endpoint);
+
+        BackendStatus {
+            profile: ProfileKind::Managed,
+            running: current.is_some(),
+            stopping: supervisor.stopping.is_some(),
+            logs_available: current.map_or(true, |m| m.child.is_some()),
+            pid: current.map(|m| m.pid),
+            started_at: current.map(|m| m.started_at),
+            last_exit: supervisor.last_exit.clone(),
+            host: endpoint.map(|e| e.host.clone()),
+            port: endpoint.map(|e| e.port),
+            base_url: endpoint.map(Endpoint::base_url),
+            health: supervisor.health.clone(),
+        }
+    }
+
+    /// Endpoint of the running backend, for commands that talk to it.
+    pub async fn endpoint(&self) -> Result<Endpoint, BackendError> {
+        let mut supervisor = self.0.lock().await;
+        supervisor.reap();
+        match (&supervisor.current, &supervisor.launch) {
+            (Some(_), Some(spec)) => Ok(spec.endpoint.clone()),
+            _ => Err(BackendError::NotRunning),
+        }
+    }
+
+    pub async fn health(&self) -> Option<HealthSnapshot> {
+        self.0.lock().await.health.clone()
+    }
+
+    /// Stores a health check result, unless the backend stopped meanwhile.
+    pub async fn record_health(&self, snapshot: HealthSnapshot) {
+        let mut supervisor = self.0.lock().await;
+        if supervisor.current.is_some() {
+            supervisor.health = Some(snapshot);
+        }
+    }
+
+    /// Takes the pending unexpected exit, if the backend died since the last check.
+    pub async fn take_crash(&self) -> Option<Crash> {
+        let mut supervisor = self.0.lock().await;
+        supervisor.reap();
+        supervisor.crash.take()
+    }
+
+    /// Relaunches the backend after `crash`, unless the user started or
+    /// stopped it in the meantime. Returns the new pid when a restart happened.
+    pub async fn restart_after(&self, crash: &Crash) -> Result<Option<u32>, BackendError> {
+        let mut supervisor = self.0.lock().await;
+        if supervisor.generation != crash.generation || supervisor.ensure_idle().is_err() {
+            return Ok(None);
+        }
+        let spec = match supervisor.launch.clone() {
+            Some(spec) => spec,
+            None => return Ok(None),
+        };
+        supervisor.spawn(&spec).map(Some)
+    }
+}
+
+/// Sends SIGTERM and waits up to [`STOP_TIMEOUT`], then falls back to SIGKILL.
+async fn terminate(mut child: Child) -> std::io::Result<ExitStatus> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: `pid` belongs to a child we spawned and have not reaped yet.
+        if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0 {
+            if let Ok(status) = tokio::time::timeout(STOP_TIMEOUT, child.wait()).await {
+                return status;
+            }
+            eprintln!("Backend did not exit within {:?}, killing it", STOP_TIMEOUT);
+        }
+    }
+
+    child.kill().await?;
+    child.wait().await
+}
+
+/// Like [`terminate`], for a backend we did not spawn and cannot wait on.
+#[cfg(unix)]
+async fn terminate_pid(pid: u32) -> std::io::Result<()> {
+    // SAFETY: callers checked that `pid` runs an `llm-verifier` backend.
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let deadline = tokio::time::Instant::now() + STOP_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        if !lockfile::is_alive(pid) {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    eprintln!("Backend did not exit within {:?}, killing it", STOP_TIMEOUT);
+    // SAFETY: as above.
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn terminate_pid(_pid: u32) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "terminating backends from a previous session is only supported on Unix",
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn refuses_to_start_until_stop_has_finished() {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-backend-{}-stopping",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let lock = dir.join("backend.lock");
+        let backend = BackendProcess::new(BackendLogs::default());
+        backend.attach_lockfile(lock.clone()).await;
+
+        // Takes a moment to exit after SIGTERM, like a server draining requests.
+        let spec = LaunchSpec::new(
+            "sh",
+            [
+                "-c",
+                "trap 'sleep 1; exit 0' TERM; while :; do sleep 0.1; done",
+            ],
+            Endpoint::local(1),
+        );
+        backend.start(spec.clone()).await.unwrap();
+        assert!(lock.exists());
+        // Let the shell install its trap.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let (stopped, (status, started, locked)) = tokio::join!(backend.stop(), async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            (
+                backend.status().await,
+                backend.start(spec.clone()).await,
+                lock.exists(),
+            )
+        });
+        assert!(stopped.is_ok());
+        assert!(status.stopping && !status.running);
+        assert!(matches!(started, Err(BackendError::Stopping(_))));
+        assert!(locked, "lockfile removed before the backend exited");
+        assert!(!lock.exists());
+        assert!(!backend.status().await.stopping);
+
+        backend.start(spec).await.unwrap();
+        backend.stop().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,185 @@
+//! Locates the `llm-verifier` binary in dev and bundled builds.
+//!
+//! `tauri.conf.json` ships the backend as `externalBin`. The bundler drops the
+//! target-triple suffix and places it next to the app executable, while
+//! `tauri dev` copies `llm-verifier-<triple>` into the target directory.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::BackendError;
+
+/// File name of the backend binary, as listed in `externalBin`.
+pub const BINARY_NAME: &str = "llm-verifier";
+
+/// Where a candidate binary came from, in resolution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinarySource {
+    /// `backend.binary_path` in the desktop config.
+    Config,
+    /// Sidecar next to the app executable.
+    Sidecar,
+    Path,
+    /// `go build` output in the repository checkout (debug builds only).
+    Dev,
+}
+
+/// A location that was tried and rejected.
+#[derive(Debug, Clone, Serialize)]
+pub struct Rejected {
+    pub source: BinarySource,
+    pub path: Option<PathBuf>,
+    pub reason: String,
+}
+
+/// The binary that will be launched, and why earlier candidates were skipped.
+#[derive(Debug, Clone, Serialize)]
+pub struct Resolution {
+    pub path: PathBuf,
+    pub source: BinarySource,
+    pub rejected: Vec<Rejected>,
+}
+
+/// Tries the configured path, the sidecar location, `$PATH` and the dev
+/// checkout, in that order, and returns the first usable binary.
+pub fn resolve(configured: Option<&Path>) -> Result<Resolution, BackendError> {
+    let mut rejected = Vec::new();
+
+    match configured {
+        Some(path) => {
+            if let Some(path) = accept(BinarySource::Config, path.to_path_buf(), &mut rejected) {
+                return Ok(found(path, BinarySource::Config, rejected));
+            }
+        }
+        None => rejected.push(Rejected {
+            source: BinarySource::Config,
+            path: None,
+            reason: "backend.binary_path is not set".to_string(),
+        }),
+    }
+
+    match sidecar_candidates() {
+        Ok(candidates) => {
+            for path in candidates {
+                if let Some(path) = accept(BinarySource::Sidecar, path, &mut rejected) {
+                    return Ok(found(path, BinarySource::Sidecar, rejected));
+                }
+            }
+        }
+        Err(reason) => rejected.push(Rejected {
+            source: BinarySource::Sidecar,
+            path: None,
+            reason,
+        }),
+    }
+
+    match env::var_os("PATH") {
+        Some(paths) => {
+            let mut on_path = false;
+            for dir in env::split_paths(&paths) {
+                let path = dir.join(file_name(BINARY_NAME));
+                if is_executable(&path) {
+                    return Ok(found(path, BinarySource::Path, rejected));
+                }
+                on_path |= path.exists();
+            }
+            rejected.push(Rejected {
+                source: BinarySource::Path,
+                path: None,
+                reason: if on_path {
+                    format!("{} on $PATH is not executable", BINARY_NAME)
+                } else {
+                    format!("{} is not on $PATH", BINARY_NAME)
+                },
+            });
+        }
+        None => rejected.push(Rejected {
+            source: BinarySource::Path,
+            path: None,
+            reason: "$PATH is not set".to_string(),
+        }),
+    }
+
+    if cfg!(debug_assertions) {
+        if let Some(path) = accept(BinarySource::Dev, dev_binary(), &mut rejected) {
+            return Ok(found(path, BinarySource::Dev, rejected));
+        }
+    } else {
+        rejected.push(Rejected {
+            source: BinarySource::Dev,
+            path: None,
+            reason: "only used in debug builds".to_string(),
+        });
+    }
+
+    Err(BackendError::NotFound(rejected))
+}
+
+fn found(path: PathBuf, source: BinarySource, rejected: Vec<Rejected>) -> Resolution {
+    Resolution {
+        path,
+        source,
+        rejected,
+    }
+}
+
+/// Returns `path` if it is an executable file, otherwise records why not.
+fn accept(source: BinarySource, path: PathBuf, rejected: &mut Vec<Rejected>) -> Option<PathBuf> {
+    let reason = if !path.exists() {
+        "does not exist"
+    } else if !path.is_file() {
+        "is not a file"
+    } else if !is_executable(&path) {
+        "is not executable"
+    } else {
+        return Some(path);
+    };
+    rejected.push(Rejected {
+        source,
+        path: Some(path),
+        reason: reason.to_string(),
+    });
+    None
+}
+
+/// Bundled sidecar first, then the suffixed name `tauri dev` leaves behind.
+fn sidecar_candidates() -> Result<Vec<PathBuf>, String> {
+    let exe = env::current_exe().map_err(|e| format!("cannot locate app executable: {}", e))?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| "app executable has no parent directory".to_string())?;
+
+    let mut candidates = vec![dir.join(file_name(BINARY_NAME))];
+    match tauri::utils::platform::target_triple() {
+        Ok(triple) => candidates.push(dir.join(file_name(&format!("{}-{}", BINARY_NAME, triple)))),
+        Err(e) => eprintln!("Failed to determine target triple: {}", e),
+    }
+    Ok(candidates)
+}
+
+/// `llm-verifier/llm-verifier`, three levels above this crate.
+fn dev_binary() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../..")
+        .join(file_name(BINARY_NAME))
+}
+
+fn file_name(stem: &str) -> String {
+    format!("{}{}", stem, env::consts::EXE_SUFFIX)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,1173 @@
+//! Loading, validating and saving the backend's `config.yaml`.
+//!
+//! Saves apply a list of edits to the file text through [`yaml_edit`], so
+//! everything the user did not change, including comments and `${ENV}`
+//! placeholders, is written back untouched. Validation mirrors
+//! `validateConfig` in `llmverifier/config_loader.go`, which the server runs
+//! on the file at startup; unlike the server, every failing rule is reported
+//! rather than only the first.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::launch::LaunchOptions;
+use super::yaml_edit::{self, Document};
+use super::{BackendError, BackendProcess};
+use crate::config::{self, describe_fields, FieldError};
+use crate::watcher::OwnWrites;
+
+/// The Go CLI's default for `--config`, relative to its working directory.
+const DEFAULT_CONFIG_FILE: &str = "config.yaml";
+
+/// `api.jwt_secret` as shipped; the server refuses it under a production profile.
+const DEFAULT_JWT_SECRET: &str = "your-secret-key-change-in-production";
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// A change to `config.yaml`, addressed by a dotted path such as
+/// `api.port` or `llms[0].api_key`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ConfigEdit {
+    Set { path: String, value: Value },
+    Remove { path: String },
+}
+
+/// `config.yaml` as shown to the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendConfigFile {
+    pub path: PathBuf,
+    pub exists: bool,
+    /// Raw file contents.
+    pub text: String,
+    /// Parsed values, with `${ENV}` placeholders left as written.
+    pub values: Value,
+    /// What the backend would reject; empty when the file is valid.
+    pub errors: Vec<FieldError>,
+}
+
+/// Result of [`save_backend_config`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedBackendConfig {
+    pub file: BackendConfigFile,
+    /// Whether the managed backend was restarted to pick up the change.
+    pub restarted: bool,
+}
+
+/// Errors surfaced by the backend config commands.
+#[derive(Debug, thiserror::Error)]
+pub enum BackendConfigError {
+    #[error("{0}")]
+    Io(String),
+    #[error("Failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+    #[error("Cannot apply edit to `{path}`: {message}")]
+    Edit { path: String, message: String },
+    #[error("Invalid backend config: {}", describe_fields(.0))]
+    Invalid(Vec<FieldError>),
+    #[error("Config saved, but restarting the backend failed: {0}")]
+    Restart(BackendError),
+}
+
+impl BackendConfigError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::Parse { .. } => "parse",
+            Self::Edit { .. } => "edit",
+            Self::Invalid(_) => "invalid",
+            Self::Restart(_) => "restart",
+        }
+    }
+}
+
+// Commands hand errors to the webview as `{ kind, message, details }`.
+impl Serialize for BackendConfigError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::Invalid(fields) => map.serialize_entry("details", fields)?,
+            Self::Restart(e) => map.serialize_entry("details", e)?,
+            _ => map.serialize_entry("details", &())?,
+        }
+        map.end()
+    }
+}
+
+/// The file the backend is started with: `backend.config_path`, or
+/// `config.yaml` in the working directory it inherits from the app.
+pub fn config_path(options: &LaunchOptions) -> Result<PathBuf, BackendConfigError> {
+    match &options.config_path {
+        Some(path) => Ok(path.clone()),
+        None => std::env::current_dir()
+            .map(|dir| dir.join(DEFAULT_CONFIG_FILE))
+            .map_err(|e| {
+                BackendConfigError::Io(format!("Failed to resolve working directory: {}", e))
+            }),
+    }
+}
+
+fn launch_options(app: &AppHandle) -> Result<LaunchOptions, BackendConfigError> {
+    LaunchOptions::load(app).map_err(|e| BackendConfigError::Io(e.to_string()))
+}
+
+/// Parses `text` and validates it with the backend's environment.
+pub fn inspect(
+    path: &Path,
+    exists: bool,
+    text: String,
+    options: &LaunchOptions,
+) -> Result<BackendConfigFile, BackendConfigError> {
+    let values: Value = if text.trim().is_empty() {
+        Value::Null
+    } else {
+        serde_yaml::from_str(&text).map_err(|e| BackendConfigError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?
+    };
+    let env = |name: &str| {
+        options
+            .env
+            .get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+            .filter(|value| !value.is_empty())
+    };
+    let errors = validate(&Fields {
+        root: &values,
+        env: &env,
+    });
+    Ok(BackendConfigFile {
+        path: path.to_path_buf(),
+        exists,
+        text,
+        values,
+        errors,
+    })
+}
+
+fn read(path: &Path) -> Result<(bool, String), BackendConfigError> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok((true, text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok((false, String::new())),
+        Err(e) => Err(BackendConfigError::Io(format!(
+            "Failed to read {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+fn apply(text: &str, edits: &[ConfigEdit]) -> Result<String, BackendConfigError> {
+    let mut document = Document::parse(text);
+    for edit in edits {
+        let (path, result) = match edit {
+            ConfigEdit::Set { path, value } => (
+                path,
+                yaml_edit::parse_path(path).and_then(|segments| document.set(&segments, value)),
+            ),
+            ConfigEdit::Remove { path } => (
+                path,
+                yaml_edit::parse_path(path).and_then(|segments| document.remove(&segments)),
+            ),
+        };
+        result.map_err(|message| BackendConfigError::Edit {
+            path: path.clone(),
+            message,
+        })?;
+    }
+    Ok(document.text())
+}
+
+/// Read access to the parsed YAML with viper's defaults and weak typing.
+struct Fields<'a> {
+    root: &'a Value,
+    env: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl<'a> Fields<'a> {
+    fn get(&self, path: &str) -> Option<&'a Value> {
+        path.split('.')
+            .try_fold(self.root, |value, key| value.get(key))
+            .filter(|value| !value.is_null())
+    }
+
+    fn string(&self, path: &str, default: &str) -> String {
+        match self.get(path) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Bool(b)) => b.to_string(),
+            Some(_) | None => default.to_string(),
+        }
+    }
+
+    /// A string the Go loader passes through `expandEnvVar`.
+    fn expanded(&self, path: &str, default: &str) -> String {
+        expand_env(&self.string(path, default), self.env)
+    }
+
+    fn int(&self, path: &str, default: i64, errors: &mut Vec<FieldError>) -> i64 {
+        match self.get(path) {
+            None => default,
+            Some(Value::Number(n)) if n.is_i64() => n.as_i64().unwrap_or(default),
+            Some(Value::String(s)) if s.trim().parse::<i64>().is_ok() => {
+                s.trim().parse().unwrap_or(default)
+            }
+            Some(other) => {
+                add(errors, path, &format!("expected an integer, got {}", other));
+                default
+            }
+        }
+    }
+
+    fn bool(&self, path: &str, default: bool) -> bool {
+        match self.get(path) {
+            Some(Value::Bool(b)) => *b,
+            Some(Value::String(s)) => {
+                matches!(s.as_str(), "1" | "t" | "T" | "true" | "TRUE" | "True")
+            }
+            Some(Value::Number(n)) => n.as_i64() != Some(0),
+            _ => default,
+        }
+    }
+
+    /// A `time.Duration` in nanoseconds: a Go duration string, or a bare
+    /// number of nanoseconds.
+    fn duration(&self, path: &str, default: i128, errors: &mut Vec<FieldError>) -> i128 {
+        match self.get(path) {
+            None => default,
+            Some(Value::Number(n)) => n.as_i64().map_or(default, i128::from),
+            Some(Value::String(s)) => match parse_go_duration(s) {
+                Some(nanos) => nanos,
+                None => {
+                    add(errors, path, &format!("invalid duration \"{}\"", s));
+                    default
+                }
+            },
+            Some(other) => {
+                add(errors, path, &format!("expected a duration, got {}", other));
+                default
+            }
+        }
+    }
+}
+
+fn add(errors: &mut Vec<FieldError>, field: &str, message: &str) {
+    errors.push(FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    });
+}
+
+/// `expandEnvVar` from the Go loader: a whole-value `${VAR}` is kept as
+/// written when unset; anything else goes through `os.ExpandEnv`.
+fn expand_env(value: &str, env: &dyn Fn(&str) -> Option<String>) -> String {
+    if value.starts_with("${") && value.ends_with('}') {
+        return env(&value[2..value.len() - 1]).unwrap_or_else(|| value.to_string());
+    }
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+        if consumed == 0 {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        out.push_str(&env(name).unwrap_or_default());
+        rest = &after[consumed..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Parses Go's `time.ParseDuration` format, e.g. `1m30s` or `250ms`.
+fn parse_go_duration(text: &str) -> Option<i128> {
+    let (negative, mut rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    if rest == "0" {
+        return Some(0);
+    }
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0f64;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_end] {
+            "ns" => 1.0,
+            "us" | "µs" | "μs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return None,
+        };
+        total += number * unit;
+        rest = &rest[unit_end..];
+    }
+    let nanos = total as i128;
+    Some(if negative { -nanos } else { nanos })
+}
+
+/// Formats nanoseconds like Go's `time.Duration.String`, e.g. `1m30s`.
+fn format_go_duration(nanos: i128) -> String {
+    fn decimal(value: i128, scale: i128) -> String {
+        let fraction = format!(
+            "{:0width$}",
+            value % scale,
+            width = scale.to_string().len() - 1
+        );
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            (value / scale).to_string()
+        } else {
+            format!("{}.{}", value / scale, fraction)
+        }
+    }
+
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let sign = if nanos < 0 { "-" } else { "" };
+    let nanos = nanos.abs();
+    if nanos < NANOS_PER_SECOND {
+        let (scale, unit) = match nanos {
+            n if n < 1_000 => (1, "ns"),
+            n if n < 1_000_000 => (1_000, "µs"),
+            _ => (1_000_000, "ms"),
+        };
+        return format!("{}{}{}", sign, decimal(nanos, scale), unit);
+    }
+    let hours = nanos / (3600 * NANOS_PER_SECOND);
+    let minutes = nanos / (60 * NANOS_PER_SECOND) % 60;
+    let seconds = decimal(nanos % (60 * NANOS_PER_SECOND), NANOS_PER_SECOND);
+    match (hours, minutes) {
+        (0, 0) => format!("{}{}s", sign, seconds),
+        (0, _) => format!("{}{}m{}s", sign, minutes, seconds),
+        _ => format!("{}{}h{}m{}s", sign, hours, minutes, seconds),
+    }
+}
+
+/// `strconv.Atoi` followed by the 1..=65535 range check.
+fn is_valid_port(port: &str) -> bool {
+    port.parse::<i64>()
+        .map_or(false, |port| (1..=65535).contains(&port))
+}
+
+/// Adds `message` unless `path` (or its default) is within `range`.
+fn check_int(
+    fields: &Fields,
+    errors: &mut Vec<FieldError>,
+    path: &str,
+    default: i64,
+    range: std::ops::RangeInclusive<i64>,
+    message: &str,
+) -> i64 {
+    let value = fields.int(path, default, errors);
+    if !range.contains(&value) {
+        add(errors, path, &format!("{}, got {}", message, value));
+    }
+    value
+}
+
+/// Adds `message` unless the duration at `path` (or its default) is within `range`.
+fn check_duration(
+    fields: &Fields,
+    errors: &mut Vec<FieldError>,
+    path: &str,
+    default: i128,
+    range: std::ops::RangeInclusive<i128>,
+    message: &str,
+) {
+    let value = fields.duration(path, default, errors);
+    if !range.contains(&value) {
+        add(
+            errors,
+            path,
+            &format!("{}, got {}", message, format_go_duration(value)),
+        );
+    }
+}
+
+/// `validateConfig` from the Go loader, applied after `setDefaults` and
+/// `expandEnvironmentVariables`. Field names are the YAML paths; messages
+/// match the Go code.
+fn validate(fields: &Fields) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if fields.expanded("api.jwt_secret", DEFAULT_JWT_SECRET) == DEFAULT_JWT_SECRET
+        && matches!(
+            (fields.env)("LLM_VERIFIER_PROFILE").as_deref(),
+            Some("prod") | Some("production")
+        )
+    {
+        add(
+            &mut errors,
+            "api.jwt_secret",
+            "production deployment detected: JWT secret must be changed from default value",
+        );
+    }
+
+    check_int(
+        fields,
+        &mut errors,
+        "concurrency",
+        1,
+        1..=100,
+        "concurrency must be between 1 and 100",
+    );
+    check_duration(
+        fields,
+        &mut errors,
+        "timeout",
+        60 * NANOS_PER_SECOND,
+        NANOS_PER_SECOND..=600 * NANOS_PER_SECOND,
+        "timeout must be between 1s and 10m",
+    );
+
+    let base_url = fields.expanded("global.base_url", "");
+    if !base_url.is_empty() && !base_url.starts_with("http") {
+        add(
+            &mut errors,
+            "global.base_url",
+            "base_url must start with http:// or https://",
+        );
+    }
+    check_int(
+        fields,
+        &mut errors,
+        "global.max_retries",
+        3,
+        0..=10,
+        "max_retries must be between 0 and 10",
+    );
+    check_duration(
+        fields,
+        &mut errors,
+        "global.request_delay",
+        NANOS_PER_SECOND,
+        0..=60 * NANOS_PER_SECOND,
+        "request_delay must be between 0 and 1m",
+    );
+    check_duration(
+        fields,
+        &mut errors,
+        "global.timeout",
+        30 * NANOS_PER_SECOND,
+        NANOS_PER_SECOND..=600 * NANOS_PER_SECOND,
+        "timeout must be between 1s and 10m",
+    );
+
+    if fields.string("database.path", "llm_verifier.db").is_empty() {
+        add(
+            &mut errors,
+            "database.path",
+            "database path cannot be empty",
+        );
+    }
+    let encryption_key = fields.expanded("database.encryption_key", "");
+    if !encryption_key.is_empty() && encryption_key.len() < 16 {
+        add(
+            &mut errors,
+            "database.encryption_key",
+            "encryption key must be at least 16 characters long",
+        );
+    }
+
+    let port = fields.string("api.port", "8080");
+    if !is_valid_port(&port) {
+        add(
+            &mut errors,
+            "api.port",
+            &format!("API port must be between 1 and 65535, got {}", port),
+        );
+    }
+    let rate_limit = check_int(
+        fields,
+        &mut errors,
+        "api.rate_limit",
+        100,
+        1..=10000,
+        "rate_limit must be between 1 and 10000",
+    );
+    let burst_limit = fields.int("api.burst_limit", 200, &mut errors);
+    if burst_limit < rate_limit {
+        add(
+            &mut errors,
+            "api.burst_limit",
+            &format!(
+                "burst_limit ({}) must be greater than or equal to rate_limit ({})",
+                burst_limit, rate_limit
+            ),
+        );
+    }
+    check_int(
+        fields,
+        &mut errors,
+        "api.rate_limit_window",
+        60,
+        1..=3600,
+        "rate_limit_window must be between 1 and 3600 seconds",
+    );
+
+    let llms = fields
+        .get("llms")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice);
+    for (i, llm) in llms.iter().enumerate() {
+        let item = Fields {
+            root: llm,
+            env: fields.env,
+        };
+        let field = |name: &str| format!("llms[{}].{}", i, name);
+        if item.expanded("name", "").trim().is_empty() {
+            add(
+                &mut errors,
+                &field("name"),
+                &format!("LLM[{}] name cannot be empty", i),
+            );
+        }
+        let endpoint = item.expanded("endpoint", "");
+        if endpoint.is_empty() {
+            add(
+                &mut errors,
+                &field("endpoint"),
+                &format!("LLM[{}] endpoint cannot be empty", i),
+            );
+        } else if !endpoint.starts_with("http") {
+            add(
+                &mut errors,
+                &field("endpoint"),
+                &format!("LLM[{}] endpoint must start with http:// or https://", i),
+            );
+        }
+        let local = endpoint.contains("localhost") || endpoint.contains("127.0.0.1");
+        if item.expanded("api_key", "").is_empty() && !local {
+            add(
+                &mut errors,
+                &field("api_key"),
+                "LLM API key is required for non-local endpoints",
+            );
+        }
+    }
+
+    let level = fields.string("logging.level", "info");
+    if !["debug", "info", "warn", "error"].contains(&level.as_str()) {
+        add(
+            &mut errors,
+            "logging.level",
+            &format!(
+                "invalid log level '{}', must be one of: [debug info warn error]",
+                level
+            ),
+        );
+    }
+    let format = fields.string("logging.format", "text");
+    if !["json", "text"].contains(&format.as_str()) {
+        add(
+            &mut errors,
+            "logging.format",
+            &format!(
+                "invalid log format '{}', must be one of: [json text]",
+                format
+            ),
+        );
+    }
+    let output = fields.string("logging.output", "stdout");
+    if !["stdout", "stderr", "file"].contains(&output.as_str()) {
+        add(
+            &mut errors,
+            "logging.output",
+            &format!(
+                "invalid log output '{}', must be one of: [stdout stderr file]",
+                output
+            ),
+        );
+    }
+    if output == "file" && fields.expanded("logging.file_path", "").is_empty() {
+        add(
+            &mut errors,
+            "logging.file_path",
+            "file_path is required when output is 'file'",
+        );
+    }
+    check_int(
+        fields,
+        &mut errors,
+        "logging.max_size",
+        100,
+        1..=1000,
+        "max_size must be between 1 and 1000 MB",
+    );
+    check_int(
+        fields,
+        &mut errors,
+        "logging.max_backups",
+        3,
+        1..=10,
+        "max_backups must be between 1 and 10",
+    );
+    check_int(
+        fields,
+        &mut errors,
+        "logging.max_age",
+        28,
+        1..=365,
+        "max_age must be between 1 and 365 days",
+    );
+
+    for (kind, enabled, default_enabled, default_port) in [
+        ("metrics", "enable_metrics", false, "9090"),
+        ("health", "enable_health", true, "8086"),
+        ("profiling", "enable_profiling", false, "6060"),
+    ] {
+        let path = format!("monitoring.{}_port", kind);
+        let port = fields.string(&path, default_port);
+        if fields.bool(&format!("monitoring.{}", enabled), default_enabled) && !is_valid_port(&port)
+        {
+            add(
+                &mut errors,
+                &path,
+                &format!(
+                    "invalid {} port '{}', must be between 1 and 65535",
+                    kind, port
+                ),
+            );
+        }
+    }
+    if fields.bool("monitoring.enable_tracing", false)
+        && fields
+            .expanded("monitoring.tracing_endpoint", "")
+            .is_empty()
+    {
+        add(
+            &mut errors,
+            "monitoring.tracing_endpoint",
+            "tracing_endpoint is required when enable_tracing is true",
+        );
+    }
+
+    check_int(
+        fields,
+        &mut errors,
+        "security.csrf_token_length",
+        32,
+        16..=128,
+        "csrf_token_length must be between 16 and 128",
+    );
+    check_int(
+        fields,
+        &mut errors,
+        "security.session_timeout",
+        60,
+        5..=1440,
+        "session_timeout must be between 5 and 1440 minutes",
+    );
+
+    errors
+}
+
+#[tauri::command]
+pub async fn load_backend_config(app: AppHandle) -> Result<BackendConfigFile, BackendConfigError> {
+    let options = launch_options(&app)?;
+    let path = config_path(&options)?;
+    let (exists, text) = read(&path)?;
+    inspect(&path, exists, text, &options)
+}
+
+/// Applies `edits` without saving and reports the resulting file and errors.
+#[tauri::command]
+pub async fn preview_backend_config(
+    app: AppHandle,
+    edits: Vec<ConfigEdit>,
+) -> Result<BackendConfigFile, BackendConfigError> {
+    let options = launch_options(&app)?;
+    let path = config_path(&options)?;
+    let (exists, text) = read(&path)?;
+    inspect(&path, exists, apply(&text, &edits)?, &options)
+}
+
+/// Applies `edits` and saves the file if the backend would accept it. With
+/// `restart`, a running managed backend is restarted to load the new file.
+#[tauri::command]
+pub async fn save_backend_config(
+    app: AppHandle,
+    edits: Vec<ConfigEdit>,
+    restart: Option<bool>,
+) -> Result<SavedBackendConfig, BackendConfigError> {
+    let options = launch_options(&app)?;
+    let path = config_path(&options)?;
+    let (_, text) = read(&path)?;
+    let file = inspect(&path, true, apply(&text, &edits)?, &options)?;
+    if !file.errors.is_empty() {
+        return Err(BackendConfigError::Invalid(file.errors));
+    }
+
+    app.state::<OwnWrites>().record(&path, &file.text);
+    config::write_atomic(&path, file.text.as_bytes()).map_err(|e| {
+        BackendConfigError::Io(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+    println!("Saved backend config {}", path.display());
+
+    let running = app.state::<BackendProcess>().status().await.running;
+    let restarted = restart.unwrap_or(false) && running;
+    if restarted {
+        crate::stop_backend(app.state(), app.state())
+            .await
+            .map_err(BackendConfigError::Restart)?;
+        crate::launch_managed(&app)
+            .await
+            .map_err(BackendConfigError::Restart)?;
+    }
+    Ok(SavedBackendConfig { file, restarted })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../../../../config.yaml.example");
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "OPENAI_API_KEY" => Some("sk-test".to_string()),
+            "HOST" => Some("localhost".to_string()),
+            _ => None,
+        }
+    }
+
+    /// `(field, message)` of every error in `yaml`.
+    fn errors(yaml: &str) -> Vec<(String, String)> {
+        let values: Value = serde_yaml::from_str(yaml).unwrap();
+        validate(&Fields {
+            root: &values,
+            env: &env,
+        })
+        .into_iter()
+        .map(|e| (e.field, e.message))
+        .collect()
+    }
+
+    fn error(field: &str, message: &str) -> Vec<(String, String)> {
+        vec![(field.to_string(), message.to_string())]
+    }
+
+    #[test]
+    fn parses_go_durations() {
+        assert_eq!(parse_go_duration("60s"), Some(60 * NANOS_PER_SECOND));
+        assert_eq!(parse_go_duration("1m30s"), Some(90 * NANOS_PER_SECOND));
+        assert_eq!(parse_go_duration("250ms"), Some(250_000_000));
+        assert_eq!(parse_go_duration("1.5h"), Some(5400 * NANOS_PER_SECOND));
+        assert_eq!(parse_go_duration("10us"), Some(10_000));
+        assert_eq!(parse_go_duration("10µs"), Some(10_000));
+        assert_eq!(parse_go_duration("-2s"), Some(-2 * NANOS_PER_SECOND));
+        assert_eq!(parse_go_duration("0"), Some(0));
+        assert_eq!(parse_go_duration(""), None);
+        assert_eq!(parse_go_duration("10"), None);
+        assert_eq!(parse_go_duration("1x"), None);
+        assert_eq!(parse_go_duration("s"), None);
+    }
+
+    #[test]
+    fn expands_env_like_the_go_loader() {
+        // A whole-value placeholder stays as written when unset.
+        assert_eq!(expand_env("${OPENAI_API_KEY}", &env), "sk-test");
+        assert_eq!(expand_env("${MISSING}", &env), "${MISSING}");
+        // Anything else goes through os.ExpandEnv.
+        assert_eq!(
+            expand_env("http://$HOST:${MISSING}1", &env),
+            "http://localhost:1"
+        );
+        assert_eq!(expand_env("a${HOST}b", &env), "alocalhostb");
+        assert_eq!(expand_env("cost $", &env), "cost $");
+        assert_eq!(expand_env("plain", &env), "plain");
+    }
+
+    #[test]
+    fn accepts_example_and_defaults() {
+        assert_eq!(errors(EXAMPLE), Vec::new());
+        assert_eq!(errors("{}"), Vec::new());
+    }
+
+    #[test]
+    fn formats_go_durations() {
+        assert_eq!(format_go_duration(0), "0s");
+        assert_eq!(format_go_duration(500_000_000), "500ms");
+        assert_eq!(format_go_duration(1_500), "1.5µs");
+        assert_eq!(format_go_duration(90 * NANOS_PER_SECOND), "1m30s");
+        assert_eq!(format_go_duration(660 * NANOS_PER_SECOND), "11m0s");
+        assert_eq!(format_go_duration(3600 * NANOS_PER_SECOND), "1h0m0s");
+        assert_eq!(format_go_duration(-2_500_000_000), "-2.5s");
+    }
+
+    #[test]
+    fn validates_jwt_secret_for_production() {
+        let production = |name: &str| match name {
+            "LLM_VERIFIER_PROFILE" => Some("production".to_string()),
+            _ => None,
+        };
+        let check = |yaml: &str| {
+            let values: Value = serde_yaml::from_str(yaml).unwrap();
+            validate(&Fields {
+                root: &values,
+                env: &production,
+            })
+            .into_iter()
+            .map(|e| e.field)
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(check("{}"), vec!["api.jwt_secret".to_string()]);
+        assert_eq!(check("api: {jwt_secret: changed}"), Vec::<String>::new());
+        // Outside production the default secret only earns a warning.
+        assert_eq!(errors("{}"), Vec::new());
+    }
+
+    #[test]
+    fn validates_concurrency_and_timeout() {
+        assert_eq!(
+            errors("concurrency: 0"),
+            error(
+                "concurrency",
+                "concurrency must be between 1 and 100, got 0"
+            )
+        );
+        assert_eq!(
+            errors("concurrency: 101"),
+            error(
+                "concurrency",
+                "concurrency must be between 1 and 100, got 101"
+            )
+        );
+        assert_eq!(
+            errors("timeout: 500ms"),
+            error("timeout", "timeout must be between 1s and 10m, got 500ms")
+        );
+        assert_eq!(
+            errors("timeout: 11m"),
+            error("timeout", "timeout must be between 1s and 10m, got 11m0s")
+        );
+        assert_eq!(errors("timeout: 10m"), Vec::new());
+        assert_eq!(
+            errors("timeout: soon")[0],
+            (
+                "timeout".to_string(),
+                "invalid duration \"soon\"".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn validates_global() {
+        assert_eq!(
+            errors("global: {base_url: 'ftp://example.com'}"),
+            error(
+                "global.base_url",
+                "base_url must start with http:// or https://"
+            )
+        );
+        assert_eq!(
+            errors("global: {max_retries: 11}"),
+            error(
+                "global.max_retries",
+                "max_retries must be between 0 and 10, got 11"
+            )
+        );
+        assert_eq!(errors("global: {max_retries: 0}"), Vec::new());
+        assert_eq!(
+            errors("global: {request_delay: 2m}"),
+            error(
+                "global.request_delay",
+                "request_delay must be between 0 and 1m, got 2m0s"
+            )
+        );
+        assert_eq!(errors("global: {request_delay: 0s}"), Vec::new());
+        assert_eq!(
+            errors("global: {timeout: 0s}"),
+            error(
+                "global.timeout",
+                "timeout must be between 1s and 10m, got 0s"
+            )
+        );
+    }
+
+    #[test]
+    fn validates_database() {
+        assert_eq!(
+            errors("database: {path: ''}"),
+            error("database.path", "database path cannot be empty")
+        );
+        assert_eq!(
+            errors("database: {encryption_key: too-short}"),
+            error(
+                "database.encryption_key",
+                "encryption key must be at least 16 characters long"
+            )
+        );
+        assert_eq!(
+            errors("database: {encryption_key: '0123456789abcdef'}"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn validates_api() {
+        assert_eq!(
+            errors("api: {port: abc}"),
+            error("api.port", "API port must be between 1 and 65535, got abc")
+        );
+        assert_eq!(
+            errors("api: {port: 70000}"),
+            error(
+                "api.port",
+                "API port must be between 1 and 65535, got 70000"
+            )
+        );
+        assert_eq!(
+            errors("api: {port: ''}"),
+            error("api.port", "API port must be between 1 and 65535, got ")
+        );
+        assert_eq!(
+            errors("api: {rate_limit: 0}"),
+            error(
+                "api.rate_limit",
+                "rate_limit must be between 1 and 10000, got 0"
+            )
+        );
+        assert_eq!(
+            errors("api: {rate_limit: 300}"),
+            error(
+                "api.burst_limit",
+                "burst_limit (200) must be greater than or equal to rate_limit (300)"
+            )
+        );
+        assert_eq!(
+            errors("api: {rate_limit: 300, burst_limit: 300}"),
+            Vec::new()
+        );
+        assert_eq!(
+            errors("api: {rate_limit_window: 3601}"),
+            error(
+                "api.rate_limit_window",
+                "rate_limit_window must be between 1 and 3600 seconds, got 3601"
+            )
+        );
+        assert_eq!(
+            errors("api: {rate_limit: lots}"),
+            error("api.rate_limit", "expected an integer, got \"lots\"")
+        );
+    }
+
+    #[test]
+    fn validates_llms() {
+        assert_eq!(
+            errors("llms: [{name: ' ', endpoint: 'https://api.openai.com/v1', api_key: k}]"),
+            error("llms[0].name", "LLM[0] name cannot be empty")
+        );
+        assert_eq!(
+            errors("llms: [{name: a, api_key: k}]"),
+            error("llms[0].endpoint", "LLM[0] endpoint cannot be empty")
+        );
+        assert_eq!(
+            errors("llms: [{name: a, api_key: k, endpoint: 'ftp://host'}]"),
+            error(
+                "llms[0].endpoint",
+                "LLM[0] endpoint must start with http:// or https://"
+            )
+        );
+        // Well-known hosts still need a key; only local endpoints go without.
+        assert_eq!(
+            errors("llms: [{name: a, endpoint: 'https://api.anthropic.com'}]"),
+            error(
+                "llms[0].api_key",
+                "LLM API key is required for non-local endpoints"
+            )
+        );
+        assert_eq!(
+            errors("llms: [{name: ollama, endpoint: 'http://localhost:11434'}]"),
+            Vec::new()
+        );
+        assert_eq!(
+            errors("llms: [{name: a, endpoint: 'http://127.0.0.1:8000/v1'}]"),
+            Vec::new()
+        );
+        // Placeholders expand before the checks.
+        assert_eq!(
+            errors("llms: [{name: a, endpoint: 'http://${HOST}:1'}]"),
+            Vec::new()
+        );
+        assert_eq!(
+            errors(
+                "llms: [{name: a, endpoint: 'https://example.com', api_key: '${OPENAI_API_KEY}'}]"
+            ),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn validates_logging() {
+        assert_eq!(
+            errors("logging: {level: verbose}"),
+            error(
+                "logging.level",
+                "invalid log level 'verbose', must be one of: [debug info warn error]"
+            )
+        );
+        assert_eq!(
+            errors("logging: {format: xml}"),
+            error(
+                "logging.format",
+                "invalid log format 'xml', must be one of: [json text]"
+            )
+        );
+        assert_eq!(
+            errors("logging: {output: syslog}"),
+            error(
+                "logging.output",
+                "invalid log output 'syslog', must be one of: [stdout stderr file]"
+            )
+        );
+        assert_eq!(
+            errors("logging: {output: file}"),
+            error(
+                "logging.file_path",
+                "file_path is required when output is 'file'"
+            )
+        );
+        assert_eq!(
+            errors("logging: {output: file, file_path: /tmp/x.log}"),
+            Vec::new()
+        );
+        assert_eq!(
+            errors("logging: {max_size: 0}"),
+            error(
+                "logging.max_size",
+                "max_size must be between 1 and 1000 MB, got 0"
+            )
+        );
+        assert_eq!(
+            errors("logging: {max_backups: 11}"),
+            error(
+                "logging.max_backups",
+                "max_backups must be between 1 and 10, got 11"
+            )
+        );
+        assert_eq!(
+            errors("logging: {max_age: 0}"),
+            error(
+                "logging.max_age",
+                "max_age must be between 1 and 365 days, got 0"
+            )
+        );
+    }
+
+    #[test]
+    fn validates_monitoring() {
+        assert_eq!(
+            errors("monitoring: {enable_metrics: true, metrics_port: ''}"),
+            error(
+                "monitoring.metrics_port",
+                "invalid metrics port '', must be between 1 and 65535"
+            )
+        );
+        assert_eq!(errors("monitoring: {metrics_port: ''}"), Vec::new());
+        assert_eq!(
+            errors("monitoring: {health_port: 0}"),
+            error(
+                "monitoring.health_port",
+                "invalid health port '0', must be between 1 and 65535"
+            )
+        );
+        assert_eq!(
+            errors("monitoring: {enable_health: false, health_port: ''}"),
+            Vec::new()
+        );
+        assert_eq!(
+            errors("monitoring: {enable_profiling: true, profiling_port: x}"),
+            error(
+                "monitoring.profiling_port",
+                "invalid profiling port 'x', must be between 1 and 65535"
+            )
+        );
+        assert_eq!(
+            errors("monitoring: {enable_tracing: true}"),
+            error(
+                "monitoring.tracing_endpoint",
+                "tracing_endpoint is required when enable_tracing is true"
+            )
+        );
+    }
+
+    #[test]
+    fn validates_security() {
+        assert_eq!(
+            errors("security: {csrf_token_length: 8}"),
+            error(
+                "security.csrf_token_length",
+                "csrf_token_length must be between 16 and 128, got 8"
+            )
+        );
+        assert_eq!(
+            errors("security: {session_timeout: 1441}"),
+            error(
+                "security.session_timeout",
+                "session_timeout must be between 5 and 1440 minutes, got 1441"
+            )
+        );
+    }
+
+    #[test]
+    fn applies_edits_and_reports_bad_paths() {
+        let edits = vec![
+            ConfigEdit::Set {
+                path: "api.port".to_string(),
+                value: Value::from("9000"),
+            },
+            ConfigEdit::Remove {
+                path: "database.encryption_key".to_string(),
+            },
+        ];
+        let text = apply(EXAMPLE, &edits).unwrap();
+        assert!(text.contains("  port: \"9000\"\n"));
+        assert!(!text.contains("encryption_key"));
+        assert!(text.contains("  api_key: \"${OPENAI_API_KEY}\" # Use environment variable"));
+
+        let bad = vec![ConfigEdit::Set {
+            path: "concurrency.max".to_string(),
+            value: Value::from(1),
+        }];
+        assert!(matches!(
+            apply(EXAMPLE, &bad),
+            Err(BackendConfigError::Edit { .. })
+        ));
+    }
+}
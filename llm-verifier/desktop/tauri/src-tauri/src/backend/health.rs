@@ -0,0 +1,214 @@
+//! Readiness gate and periodic health monitoring against `/api/health`.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use llm_verifier_client::types::HealthStatus;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::logs::BackendLogs;
+use super::port::Endpoint;
+use super::profile::{Connection, ConnectionProfile, Profiles};
+use super::{BackendError, BackendProcess};
+use crate::vault::Vault;
+
+/// Timeout of a single health request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Delay between health polls while waiting for the backend to come up.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Delay between health checks once the backend is up.
+const MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Failed checks in a row before a running backend is reported unreachable.
+const UNREACHABLE_AFTER: u32 = 3;
+
+/// Log lines attached to a readiness failure.
+const FAILURE_LOG_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Starting,
+    Healthy,
+    /// The backend answers but does not report itself healthy.
+    Degraded,
+    Unreachable,
+}
+
+/// Latest health check result, reported by `get_backend_status` and `backend://health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSnapshot {
+    pub state: HealthState,
+    pub checked_at: DateTime<Utc>,
+    pub report: Option<HealthStatus>,
+    pub error: Option<String>,
+    pub consecutive_failures: u32,
+}
+
+impl HealthSnapshot {
+    pub fn starting() -> Self {
+        Self {
+            state: HealthState::Starting,
+            checked_at: Utc::now(),
+            report: None,
+            error: None,
+            consecutive_failures: 0,
+        }
+    }
+}
+
+/// Details attached to [`BackendError::NotReady`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessFailure {
+    pub reason: String,
+    pub health: Option<HealthStatus>,
+    pub logs: Vec<String>,
+}
+
+pub fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+pub async fn check(
+    client: &reqwest::Client,
+    connection: &Connection,
+) -> Result<HealthStatus, String> {
+    let client = connection
+        .client(client.clone())
+        .map_err(|e| e.to_string())?;
+    client.get_health().await.map_err(|e| e.to_string())
+}
+
+/// Polls `/api/health` until the backend reports healthy, exits, or `timeout` passes.
+/// `log_seq` is the first log sequence number of the process being waited on.
+pub async fn wait_ready(
+    backend: &BackendProcess,
+    logs: &BackendLogs,
+    endpoint: &Endpoint,
+    log_seq: u64,
+    timeout: Duration,
+) -> Result<HealthStatus, BackendError> {
+    let client = client();
+    let connection = Connection::managed(endpoint);
+    let deadline = Instant::now() + timeout;
+    let mut last_report = None;
+    let mut last_error = None;
+
+    let failure = |reason: String, health: Option<HealthStatus>| {
+        BackendError::NotReady(Box::new(ReadinessFailure {
+            reason,
+            health,
+            logs: logs.tail(None, log_seq, FAILURE_LOG_LINES),
+        }))
+    };
+
+    loop {
+        if !backend.status().await.running {
+            return Err(failure(
+                "Backend exited during startup".to_string(),
+                last_report,
+            ));
+        }
+
+        match check(&client, &connection).await {
+            Ok(report) if report.is_healthy() => return Ok(report),
+            Ok(report) => last_report = Some(report),
+            Err(e) => last_error = Some(e),
+        }
+
+        if Instant::now() >= deadline {
+            let reason = match (&last_report, last_error) {
+                (Some(report), _) => format!("Backend reported status `{}`", report.status),
+                (None, Some(e)) => format!("Backend did not answer: {}", e),
+                (None, None) => "Backend did not answer".to_string(),
+            };
+            return Err(failure(
+                format!("{} after {:?}", reason, timeout),
+                last_report,
+            ));
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// Starts the periodic health monitor for the lifetime of the app. It checks
+/// whichever backend the active connection profile points at.
+pub fn spawn_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let backend = app.state::<BackendProcess>();
+        let profiles = app.state::<Profiles>();
+        let vault = app.state::<Vault>();
+        let client = client();
+
+        loop {
+            tokio::time::sleep(MONITOR_INTERVAL).await;
+
+            let remote = match profiles.active().await {
+                ConnectionProfile::Managed => false,
+                ConnectionProfile::Remote { .. } => true,
+            };
+            let connection = match profiles.connection(&backend, &vault).await {
+                Ok(connection) => connection,
+                Err(_) => continue,
+            };
+            let previous = if remote {
+                profiles.remote_health().await
+            } else {
+                backend.health().await
+            };
+
+            let snapshot = next_snapshot(previous.as_ref(), check(&client, &connection).await);
+            if previous.map(|h| h.state) != Some(snapshot.state) {
+                let _ = app.emit_all("backend://health", snapshot.clone());
+            }
+            if remote {
+                profiles.record_remote_health(snapshot).await;
+            } else {
+                backend.record_health(snapshot).await;
+            }
+        }
+    });
+}
+
+fn next_snapshot(
+    previous: Option<&HealthSnapshot>,
+    result: Result<HealthStatus, String>,
+) -> HealthSnapshot {
+    match result {
+        Ok(report) => HealthSnapshot {
+            state: if report.is_healthy() {
+                HealthState::Healthy
+            } else {
+                HealthState::Degraded
+            },
+            checked_at: Utc::now(),
+            report: Some(report),
+            error: None,
+            consecutive_failures: 0,
+        },
+        Err(e) => {
+            let failures = previous.map_or(0, |h| h.consecutive_failures) + 1;
+            let state = match previous.map(|h| h.state) {
+                // Still booting; give it time before calling it unreachable.
+                Some(HealthState::Starting) if failures < UNREACHABLE_AFTER => {
+                    HealthState::Starting
+                }
+                _ if failures >= UNREACHABLE_AFTER => HealthState::Unreachable,
+                _ => HealthState::Degraded,
+            };
+            HealthSnapshot {
+                state,
+                checked_at: Utc::now(),
+                report: None,
+                error: Some(e),
+                consecutive_failures: failures,
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Builds the backend command line from the desktop config and checks the
+//! binary supports it before launching.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use super::port::Endpoint;
+use super::{BackendError, LaunchSpec};
+use crate::config;
+
+/// How long `llm-verifier --help` may take before the probe gives up.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The Go CLI has no `--database` flag, but viper's `AutomaticEnv` with the
+/// `LLM_VERIFIER` prefix and a `.` to `_` key replacer maps `database.path`
+/// to this variable.
+const DATABASE_PATH_ENV: &str = "LLM_VERIFIER_DATABASE_PATH";
+
+/// Backend launch settings, read from the `backend` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchOptions {
+    /// Backend binary to launch instead of the bundled sidecar.
+    pub binary_path: Option<PathBuf>,
+    /// Cobra subcommand that starts the REST API (`llm-verifier server`).
+    pub subcommand: String,
+    /// Fixed port; a free one is picked for every start when unset.
+    pub port: Option<u16>,
+    /// Passed as the persistent `--config` flag.
+    pub config_path: Option<PathBuf>,
+    pub database_path: Option<PathBuf>,
+    /// Extra environment variables for the backend process.
+    pub env: BTreeMap<String, String>,
+    /// How long `start_backend` waits for `/api/health` to report healthy.
+    pub ready_timeout_secs: u64,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self {
+            binary_path: None,
+            subcommand: "server".to_string(),
+            port: None,
+            config_path: None,
+            database_path: None,
+            env: BTreeMap::new(),
+            ready_timeout_secs: 30,
+        }
+    }
+}
+
+impl LaunchOptions {
+    /// Reads the `backend` section of the desktop config with the active
+    /// workspace's overrides applied, falling back to defaults.
+    pub fn load(app: &AppHandle) -> Result<Self, BackendError> {
+        config::load(app)
+            .map(|config| config.workspaces.active_workspace().apply(config.backend))
+            .map_err(|e| BackendError::InvalidConfig(e.to_string()))
+    }
+
+    pub fn to_spec(&self, program: &Path, endpoint: Endpoint) -> LaunchSpec {
+        let mut args = self.global_args();
+        args.push(self.subcommand.clone());
+        args.push("--port".to_string());
+        args.push(endpoint.port.to_string());
+
+        let mut spec = LaunchSpec::new(program, args, endpoint);
+        spec.env = self.env();
+        spec
+    }
+
+    /// Runs a one-off CLI subcommand (e.g. `ai-config export`) with the same
+    /// config file and environment as the server.
+    pub fn command(&self, program: &Path, args: &[String]) -> Command {
+        let mut command = Command::new(program);
+        command
+            .args(self.global_args())
+            .args(args)
+            .envs(self.env())
+            .kill_on_drop(true);
+        command
+    }
+
+    fn global_args(&self) -> Vec<String> {
+        match &self.config_path {
+            Some(config_path) => vec!["--config".to_string(), config_path.display().to_string()],
+            None => Vec::new(),
+        }
+    }
+
+    fn env(&self) -> Vec<(String, String)> {
+        let mut env: Vec<(String, String)> = self.env.clone().into_iter().collect();
+        if let Some(database_path) = &self.database_path {
+            env.push((
+                DATABASE_PATH_ENV.to_string(),
+                database_path.display().to_string(),
+            ));
+        }
+        env
+    }
+}
+
+/// Runs `llm-verifier --help` and makes sure `subcommand` is listed.
+pub async fn probe(program: &Path, subcommand: &str) -> Result<(), BackendError> {
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        Command::new(program)
+            .arg("--help")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        BackendError::Probe(format!(
+            "`--help` did not finish within {:?}",
+            PROBE_TIMEOUT
+        ))
+    })?
+    .map_err(|e| BackendError::Probe(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(BackendError::Probe(format!(
+            "`--help` exited with {}",
+            output.status
+        )));
+    }
+
+    let available = available_commands(&String::from_utf8_lossy(&output.stdout));
+    if available.iter().any(|c| c == subcommand) {
+        Ok(())
+    } else {
+        Err(BackendError::UnsupportedMode {
+            subcommand: subcommand.to_string(),
+            available,
+        })
+    }
+}
+
+/// First line of `llm-verifier --version`, or `None` when the binary does not
+/// support the flag.
+pub async fn version(program: &Path) -> Option<String> {
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        Command::new(program)
+            .arg("--version")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+}
+
+/// Parses the `Available Commands:` section of cobra's help output.
+fn available_commands(help: &str) -> Vec<String> {
+    help.lines()
+        .skip_while(|line| !line.starts_with("Available Commands:"))
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
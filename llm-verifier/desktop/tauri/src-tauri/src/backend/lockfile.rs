@@ -0,0 +1,347 @@
+//! Records the running backend in the app data dir so a backend left behind
+//! by a killed desktop app can be found, and adopted or terminated, on the
+//! next launch.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use super::binary::BINARY_NAME;
+use super::port::Endpoint;
+use super::{BackendError, BackendProcess};
+
+const LOCK_FILE_NAME: &str = "backend.lock";
+
+/// How far a process's start time may be from the recorded `started_at` and
+/// still count as the recorded backend.
+const START_TIME_TOLERANCE_SECS: i64 = 5;
+
+/// Contents of `backend.lock`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub pid: u32,
+    pub host: String,
+    pub port: u16,
+    pub program: PathBuf,
+    pub started_at: DateTime<Utc>,
+}
+
+impl Lockfile {
+    pub fn endpoint(&self) -> Endpoint {
+        Endpoint {
+            host: self.host.clone(),
+            port: self.port,
+        }
+    }
+}
+
+pub fn path(app: &AppHandle) -> Option<PathBuf> {
+    app.path_resolver()
+        .app_data_dir()
+        .map(|dir| dir.join(LOCK_FILE_NAME))
+}
+
+pub fn read(path: &Path) -> Option<Lockfile> {
+    let contents = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(lock) => Some(lock),
+        Err(e) => {
+            eprintln!("Ignoring unreadable backend lockfile: {}", e);
+            None
+        }
+    }
+}
+
+pub fn write(path: &Path, lock: &Lockfile) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            let contents = serde_json::to_string_pretty(lock).map_err(std::io::Error::from)?;
+            fs::write(path, contents)
+        });
+    if let Err(e) = result {
+        eprintln!("Failed to write backend lockfile: {}", e);
+    }
+}
+
+pub fn remove(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => eprintln!("Failed to remove backend lockfile: {}", e),
+    }
+}
+
+/// Whether a process with `pid` exists.
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks that the process exists and may be signalled.
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub fn is_alive(_pid: u32) -> bool {
+    // No portable liveness check; treat recorded backends as gone.
+    false
+}
+
+/// Whether `pid` runs an `llm-verifier` binary, so a reused pid is never
+/// mistaken for the backend.
+fn is_backend(pid: u32) -> bool {
+    match executable_name(pid) {
+        Some(name) => name == BINARY_NAME || name.starts_with(&format!("{}-", BINARY_NAME)),
+        None => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn executable_name(pid: u32) -> Option<String> {
+    fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()?
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn executable_name(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .ok()?;
+    let comm = String::from_utf8_lossy(&output.stdout);
+    Path::new(comm.trim())
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn executable_name(_pid: u32) -> Option<String> {
+    None
+}
+
+/// When `pid` started, from `/proc/<pid>/stat` and the boot time.
+#[cfg(target_os = "linux")]
+fn start_time(pid: u32) -> Option<DateTime<Utc>> {
+    use chrono::TimeZone;
+
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name may contain spaces; `starttime` is the 20th field
+    // after the parenthesis that closes it.
+    let after_name = stat.get(stat.rfind(')')? + 2..)?;
+    let ticks: i64 = after_name.split(' ').nth(19)?.parse().ok()?;
+    let boot: i64 = fs::read_to_string("/proc/stat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+    // SAFETY: sysconf has no preconditions.
+    let hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as i64;
+    if hz <= 0 {
+        return None;
+    }
+    Utc.timestamp_millis_opt(boot * 1000 + ticks * 1000 / hz)
+        .single()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn start_time(pid: u32) -> Option<DateTime<Utc>> {
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "etime="])
+        .output()
+        .ok()?;
+    // Elapsed time as `[[dd-]hh:]mm:ss`.
+    let elapsed = String::from_utf8_lossy(&output.stdout);
+    let (days, clock) = match elapsed.trim().split_once('-') {
+        Some((days, clock)) => (days.parse::<i64>().ok()?, clock),
+        None => (0, elapsed.trim()),
+    };
+    let mut secs = days * 86_400;
+    let mut units = 0;
+    for part in clock.split(':') {
+        secs = secs * 60 + part.parse::<i64>().ok()?;
+        units += 1;
+    }
+    if units < 2 {
+        return None;
+    }
+    Some(Utc::now() - chrono::Duration::seconds(secs))
+}
+
+#[cfg(not(unix))]
+fn start_time(_pid: u32) -> Option<DateTime<Utc>> {
+    None
+}
+
+/// Checks that the process recorded in `lock` is still that backend: alive,
+/// running an `llm-verifier` binary, and started when the lockfile says. A
+/// pid reused by an unrelated process fails the last two checks.
+fn verify(lock: &Lockfile) -> Result<(), String> {
+    if !is_alive(lock.pid) {
+        return Err(format!("Backend (pid {}) has already exited", lock.pid));
+    }
+    if !is_backend(lock.pid) {
+        return Err(format!(
+            "Process {} is no longer an {} backend",
+            lock.pid, BINARY_NAME
+        ));
+    }
+    let started_at = start_time(lock.pid)
+        .ok_or_else(|| format!("Cannot read the start time of process {}", lock.pid))?;
+    if (started_at - lock.started_at).num_seconds().abs() > START_TIME_TOLERANCE_SECS {
+        return Err(format!(
+            "Process {} started at {}, not at {} as recorded",
+            lock.pid, started_at, lock.started_at
+        ));
+    }
+    Ok(())
+}
+
+/// Backend found running at startup, waiting for the user to adopt or terminate it.
+#[derive(Default)]
+pub struct Orphan(Mutex<Option<Lockfile>>);
+
+impl Orphan {
+    fn take(&self) -> Result<Lockfile, String> {
+        self.0
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| "No orphaned backend was found".to_string())
+    }
+}
+
+/// Checks the lockfile left by a previous run and emits `backend://orphan`
+/// when its backend is still alive.
+pub fn spawn_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let path = match path(&app) {
+            Some(path) => path,
+            None => return,
+        };
+        // Read before attaching, which may record a backend started meanwhile.
+        let previous = read(&path);
+        app.state::<BackendProcess>()
+            .attach_lockfile(path.clone())
+            .await;
+
+        let lock = match previous {
+            Some(lock) => lock,
+            None => return,
+        };
+        if let Err(e) = verify(&lock) {
+            // The backend exited, or its pid now belongs to something else.
+            println!("Ignoring backend lockfile: {}", e);
+            remove(&path);
+            return;
+        }
+
+        println!(
+            "Found backend from a previous session (pid {}, {})",
+            lock.pid,
+            lock.endpoint().base_url()
+        );
+        *app.state::<Orphan>().0.lock().unwrap() = Some(lock.clone());
+        let _ = app.emit_all("backend://orphan", lock);
+    });
+}
+
+/// The backend left running by a previous session, if any.
+#[tauri::command]
+pub async fn get_orphaned_backend(orphan: State<'_, Orphan>) -> Result<Option<Lockfile>, String> {
+    Ok(orphan.0.lock().unwrap().clone())
+}
+
+/// Reuses the orphaned backend as the managed backend. Its output went to
+/// the previous session, so no logs are available for it.
+#[tauri::command]
+pub async fn adopt_orphaned_backend(
+    orphan: State<'_, Orphan>,
+    backend: State<'_, BackendProcess>,
+) -> Result<String, BackendError> {
+    let lock = orphan.take().map_err(BackendError::Adopt)?;
+    // Checked again: the pid may have been reused since startup.
+    verify(&lock).map_err(BackendError::Adopt)?;
+    backend.adopt(&lock).await?;
+    Ok(format!(
+        "Adopted backend (pid {}, {}); its logs are not available",
+        lock.pid,
+        lock.endpoint().base_url()
+    ))
+}
+
+/// Stops the orphaned backend and removes its lockfile.
+#[tauri::command]
+pub async fn terminate_orphaned_backend(
+    app: AppHandle,
+    orphan: State<'_, Orphan>,
+) -> Result<String, BackendError> {
+    let lock = orphan.take().map_err(BackendError::Adopt)?;
+    if verify(&lock).is_ok() {
+        super::terminate_pid(lock.pid)
+            .await
+            .map_err(BackendError::Stop)?;
+    }
+    if let Some(path) = path(&app) {
+        // Leave the lockfile alone if a new backend has been started meanwhile.
+        if read(&path).map_or(false, |current| current.pid == lock.pid) {
+            remove(&path);
+        }
+    }
+    Ok(format!("Terminated orphaned backend (pid {})", lock.pid))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_pid_executable_and_start_time() {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-lockfile-{}-verify",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // A stand-in for the backend binary, named like it.
+        let program = dir.join(BINARY_NAME);
+        fs::copy("/bin/sleep", &program).unwrap();
+        let mut child = std::process::Command::new(&program)
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let lock = Lockfile {
+            pid: child.id(),
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            program: program.clone(),
+            started_at: Utc::now(),
+        };
+
+        assert_eq!(verify(&lock), Ok(()));
+        // A process started long after the recorded backend reuses its pid.
+        let earlier = Lockfile {
+            started_at: lock.started_at - chrono::Duration::minutes(10),
+            ..lock.clone()
+        };
+        assert!(verify(&earlier).unwrap_err().contains("not at"));
+        // Another executable under the recorded pid.
+        let other = Lockfile {
+            pid: std::process::id(),
+            ..lock.clone()
+        };
+        assert!(verify(&other).unwrap_err().contains("no longer"));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(verify(&lock).unwrap_err().contains("already exited"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
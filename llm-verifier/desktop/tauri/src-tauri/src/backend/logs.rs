@@ -0,0 +1,353 @@
+//! Captures backend stdout/stderr into a bounded buffer, live events and
+//! optional rotating log files.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::config;
+
+/// Number of lines kept in memory.
+const BUFFER_LINES: usize = 5_000;
+
+/// File name of the active log file under `<app data>/logs`.
+const LOG_FILE_NAME: &str = "backend.log";
+
+/// Mirrors `logging.LogLevel` in the Go backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "debug" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            "fatal" | "panic" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Best-effort level detection for JSON logs, the console format
+    /// (`2006-01-02 15:04:05 [INFO] source: message`) and Go panics.
+    fn detect(line: &str) -> Self {
+        if line.starts_with('{') {
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
+                if let Some(level) = value.get("level").and_then(|l| l.as_str()) {
+                    return Self::parse(level).unwrap_or(Self::Info);
+                }
+            }
+        }
+        if line.starts_with("panic:") || line.starts_with("fatal error:") {
+            return Self::Fatal;
+        }
+        if let (Some(start), Some(end)) = (line.find('['), line.find(']')) {
+            if start < end {
+                if let Some(level) = Self::parse(&line[start + 1..end]) {
+                    return level;
+                }
+            }
+        }
+        Self::Info
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// A single captured line, also the payload of `backend://log`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub stream: LogStream,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// File rotation settings, read from the `log_file` section of the desktop
+/// config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogFileOptions {
+    pub enabled: bool,
+    pub max_bytes: u64,
+    /// Rotated files kept next to `backend.log`.
+    pub max_files: u32,
+}
+
+impl Default for LogFileOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+impl LogFileOptions {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.log_file)
+            .unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_bytes == 0 {
+            return Err("`max_bytes` must be at least 1".to_string());
+        }
+        if self.max_files == 0 {
+            return Err("`max_files` must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+struct LogFile {
+    dir: PathBuf,
+    file: File,
+    written: u64,
+    options: LogFileOptions,
+}
+
+impl LogFile {
+    fn open(dir: PathBuf, options: LogFileOptions) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE_NAME))?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            file,
+            written,
+            options,
+        })
+    }
+
+    fn write(&mut self, line: &LogLine) -> std::io::Result<()> {
+        let text = format!(
+            "{} [{:?}] {}\n",
+            line.timestamp.to_rfc3339(),
+            line.stream,
+            line.message
+        );
+        if self.written + text.len() as u64 > self.options.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(text.as_bytes())?;
+        self.written += text.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts `backend.log.N` to `backend.log.N+1`, dropping the oldest.
+    /// Validation keeps `max_files` at 1 or more, so `backend.log` itself is
+    /// never the one dropped.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let path = |n: u32| match n {
+            0 => self.dir.join(LOG_FILE_NAME),
+            n => self.dir.join(format!("{}.{}", LOG_FILE_NAME, n)),
+        };
+        let _ = fs::remove_file(path(self.options.max_files));
+        for n in (0..self.options.max_files).rev() {
+            let _ = fs::rename(path(n), path(n + 1));
+        }
+        self.file = File::create(path(0))?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    lines: VecDeque<LogLine>,
+    next_seq: u64,
+    file: Option<LogFile>,
+}
+
+/// Managed state holding captured backend output. Cheap to clone; clones
+/// share the same buffer.
+#[derive(Clone, Default)]
+pub struct BackendLogs {
+    inner: Arc<Mutex<Inner>>,
+    app: Arc<Mutex<Option<AppHandle>>>,
+}
+
+impl BackendLogs {
+    /// Enables live `backend://log` events and opens the log file when the
+    /// desktop config asks for one.
+    pub fn attach(&self, app: AppHandle) {
+        if let Err(e) = open_file(&app, self, LogFileOptions::load(&app)) {
+            eprintln!("{}", e);
+        }
+        *self.app.lock().unwrap() = Some(app);
+    }
+
+    /// Sequence number the next captured line will get.
+    pub fn next_seq(&self) -> u64 {
+        self.inner.lock().unwrap().next_seq
+    }
+
+    pub fn push(&self, stream: LogStream, message: String) {
+        let message = strip_ansi(&message);
+        let line = {
+            let mut inner = self.inner.lock().unwrap();
+            let line = LogLine {
+                seq: inner.next_seq,
+                timestamp: Utc::now(),
+                stream,
+                level: LogLevel::detect(&message),
+                message,
+            };
+            inner.next_seq += 1;
+            if inner.lines.len() == BUFFER_LINES {
+                inner.lines.pop_front();
+            }
+            inner.lines.push_back(line.clone());
+            if let Some(file) = inner.file.as_mut() {
+                if let Err(e) = file.write(&line) {
+                    eprintln!("Failed to write backend log file: {}", e);
+                    inner.file = None;
+                }
+            }
+            line
+        };
+
+        if let Some(app) = self.app.lock().unwrap().as_ref() {
+            let _ = app.emit_all("backend://log", line);
+        }
+    }
+
+    /// Lines with `seq >= since` at or above `level`.
+    pub fn query(&self, since: Option<u64>, level: Option<LogLevel>) -> Vec<LogLine> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .lines
+            .iter()
+            .filter(|l| since.map_or(true, |s| l.seq >= s))
+            .filter(|l| level.map_or(true, |min| l.level >= min))
+            .cloned()
+            .collect()
+    }
+
+    /// The last `count` lines captured since `since`, from `stream` or both streams.
+    pub fn tail(&self, stream: Option<LogStream>, since: u64, count: usize) -> Vec<String> {
+        let inner = self.inner.lock().unwrap();
+        let mut tail: Vec<String> = inner
+            .lines
+            .iter()
+            .rev()
+            .filter(|l| l.seq >= since && stream.map_or(true, |s| l.stream == s))
+            .take(count)
+            .map(|l| l.message.clone())
+            .collect();
+        tail.reverse();
+        tail
+    }
+
+    pub fn configure_file(&self, dir: PathBuf, options: LogFileOptions) -> std::io::Result<()> {
+        let file = if options.enabled {
+            Some(LogFile::open(dir, options)?)
+        } else {
+            None
+        };
+        self.inner.lock().unwrap().file = file;
+        Ok(())
+    }
+
+    /// Forwards every line of `reader` into the buffer until EOF.
+    pub fn capture<R>(&self, stream: LogStream, reader: R)
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let logs = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                logs.push(stream, line);
+            }
+        });
+    }
+}
+
+/// Removes terminal colour codes such as the ones the Go console logger emits.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip `ESC [ ... <final byte>`.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[tauri::command]
+pub async fn get_backend_logs(
+    since: Option<u64>,
+    level: Option<LogLevel>,
+    logs: State<'_, BackendLogs>,
+) -> Result<Vec<LogLine>, String> {
+    Ok(logs.query(since, level))
+}
+
+/// Applies `options` to `logs`, returning the path of the active log file.
+/// Checked here too, since a hand-edited config is loaded without validation.
+pub fn open_file(
+    app: &AppHandle,
+    logs: &BackendLogs,
+    options: LogFileOptions,
+) -> Result<PathBuf, String> {
+    options
+        .validate()
+        .map_err(|e| format!("Invalid log file settings: {}", e))?;
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or("Failed to resolve app data directory")?
+        .join("logs");
+    logs.configure_file(dir.clone(), options)
+        .map_err(|e| format!("Failed to open log file: {}", e))?;
+    Ok(dir.join(LOG_FILE_NAME))
+}
+
+/// Saves `options` to the desktop config and applies them.
+#[tauri::command]
+pub async fn configure_backend_log_file(
+    options: LogFileOptions,
+    app: AppHandle,
+    logs: State<'_, BackendLogs>,
+) -> Result<String, String> {
+    let saved = options.clone();
+    config::update(&app, |config| config.log_file = saved).map_err(|e| e.to_string())?;
+    let path = open_file(&app, &logs, options)?;
+    Ok(path.display().to_string())
+}
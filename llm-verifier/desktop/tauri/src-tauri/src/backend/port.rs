@@ -0,0 +1,49 @@
+//! Chooses the port the backend listens on.
+
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, TcpListener};
+
+use serde::Serialize;
+
+use super::BackendError;
+
+/// Address the desktop app uses to reach a locally spawned backend.
+pub const LOCAL_HOST: &str = "127.0.0.1";
+
+/// Where a running backend can be reached.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Endpoint {
+    pub fn local(port: u16) -> Self {
+        Self {
+            host: LOCAL_HOST.to_string(),
+            port,
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}:{}", self.host, self.port)
+    }
+}
+
+/// Returns `preferred` if it can be bound, otherwise lets the OS pick a free port.
+///
+/// The Go server listens on `:<port>`, i.e. every interface, so availability
+/// is checked against the unspecified address rather than loopback only.
+pub fn allocate(preferred: Option<u16>) -> Result<u16, BackendError> {
+    match preferred {
+        Some(port) => match TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)) {
+            Ok(_) => Ok(port),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => Err(BackendError::PortInUse(port)),
+            Err(e) => Err(BackendError::Port(e)),
+        },
+        None => TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .map_err(BackendError::Port),
+    }
+}
@@ -0,0 +1,621 @@
+//! Connection profiles: talk to a backend spawned by the app, or to a shared
+//! `llm-verifier server` elsewhere.
+//!
+//! Remote credentials are kept in the vault. The desktop config only names
+//! the vault secret, and the webview only ever gets masked values back.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use llm_verifier_client::{Client, ClientBuilder, Routes, Token};
+use serde::{Deserialize, Deserializer, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::{Mutex, RwLock};
+use zeroize::Zeroizing;
+
+use super::health::HealthSnapshot;
+use super::port::Endpoint;
+use super::{BackendError, BackendProcess};
+use crate::config;
+use crate::vault::{self, Vault, VaultError};
+
+/// Name of the profile used when the desktop config defines none.
+const DEFAULT_PROFILE: &str = "local";
+
+/// Prefix of the vault secrets holding remote credentials. They are never
+/// handed to the managed backend.
+const CREDENTIAL_PREFIX: &str = "LLM_VERIFIER_REMOTE_";
+
+/// A password or token of a remote profile.
+#[derive(Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Credential {
+    /// Vault secret holding the value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// A new value from the webview, moved into the vault when the profiles
+    /// are saved. Never written to the config or sent back.
+    #[serde(skip_serializing)]
+    pub value: Option<String>,
+    /// Masked value, only filled in for the webview.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub masked: Option<String>,
+}
+
+// Configs written before credentials moved to the vault hold the plain
+// value; it is read as a new value so the next save moves it.
+impl<'de> Deserialize<'de> for Credential {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Plain(String),
+            Stored {
+                #[serde(default)]
+                secret: Option<String>,
+                #[serde(default)]
+                value: Option<String>,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Plain(value) => Self {
+                value: Some(value),
+                ..Self::default()
+            },
+            Repr::Stored { secret, value } => Self {
+                secret,
+                value: value.filter(|value| !value.is_empty()),
+                masked: None,
+            },
+        })
+    }
+}
+
+// Hand-written so credentials never reach a log through `{:?}`.
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("secret", &self.secret)
+            .field("value", &self.value.as_ref().map(|_| "[redacted]"))
+            .finish()
+    }
+}
+
+impl Credential {
+    /// Moves a new value into the vault as `name`. Without a new value, the
+    /// credential saved so far is kept.
+    fn seal(&mut self, vault: &Vault, name: String, current: Option<&Self>) -> Result<(), String> {
+        if self.value.is_none() && self.secret.is_none() {
+            if let Some(current) = current {
+                self.secret = current.secret.clone();
+                self.value = current.value.clone();
+            }
+        }
+        if let Some(value) = self.value.take() {
+            vault
+                .store(&name, &value)
+                .map_err(|e| format!("Failed to store `{}` in the vault: {}", name, e))?;
+            self.secret = Some(name);
+        }
+        self.masked = None;
+        Ok(())
+    }
+
+    /// The masked value, or bullets while the vault is locked.
+    fn mask(&mut self, vault: &Vault) {
+        self.masked = Some(match (&self.value, &self.secret) {
+            (Some(value), _) => vault::mask(value),
+            (None, Some(secret)) => vault.masked(secret),
+            (None, None) => String::new(),
+        });
+        self.value = None;
+    }
+
+    fn resolve(&self, vault: &Vault) -> Result<Zeroizing<String>, VaultError> {
+        match (&self.value, &self.secret) {
+            (Some(value), _) => Ok(Zeroizing::new(value.clone())),
+            (None, Some(secret)) => vault.secret(secret),
+            (None, None) => Err(VaultError::EmptyValue),
+        }
+    }
+}
+
+/// Credentials sent to a remote backend, as saved in the desktop config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteAuth {
+    None,
+    Bearer {
+        token: Credential,
+    },
+    Basic {
+        username: String,
+        password: Credential,
+    },
+}
+
+impl Default for RemoteAuth {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl RemoteAuth {
+    /// The credential, with the suffix of the vault secret it is kept in.
+    fn credential_mut(&mut self) -> Option<(&'static str, &mut Credential)> {
+        match self {
+            Self::None => None,
+            Self::Bearer { token } => Some(("TOKEN", token)),
+            Self::Basic { password, .. } => Some(("PASSWORD", password)),
+        }
+    }
+
+    fn credential(&self) -> Option<&Credential> {
+        match self {
+            Self::None => None,
+            Self::Bearer { token } => Some(token),
+            Self::Basic { password, .. } => Some(password),
+        }
+    }
+
+    fn resolve(&self, vault: &Vault) -> Result<Auth, VaultError> {
+        Ok(match self {
+            Self::None => Auth::None,
+            Self::Bearer { token } => Auth::Bearer {
+                token: token.resolve(vault)?,
+            },
+            Self::Basic { username, password } => Auth::Basic {
+                username: username.clone(),
+                password: password.resolve(vault)?,
+            },
+        })
+    }
+}
+
+/// [`RemoteAuth`] with its credential read from the vault.
+#[derive(Clone)]
+pub enum Auth {
+    None,
+    Bearer {
+        token: Zeroizing<String>,
+    },
+    Basic {
+        username: String,
+        password: Zeroizing<String>,
+    },
+}
+
+// Hand-written so credentials never reach a log through `{:?}`.
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Bearer { .. } => f.write_str("Bearer { token: [redacted] }"),
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &format_args!("[redacted]"))
+                .finish(),
+        }
+    }
+}
+
+/// Whether the vault secret `name` holds a remote credential.
+pub fn is_credential(name: &str) -> bool {
+    name.starts_with(CREDENTIAL_PREFIX)
+}
+
+/// Vault secret name for a credential of `profile`, e.g.
+/// `LLM_VERIFIER_REMOTE_SHARED_TOKEN`.
+fn credential_name(profile: &str, suffix: &str) -> String {
+    let profile: String = profile
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}{}_{}", CREDENTIAL_PREFIX, profile, suffix)
+}
+
+/// The route layout a backend serves; see [`Routes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteLayout {
+    /// `llm-verifier server`: routes under `/api` and no login endpoint.
+    Server,
+    /// The documented `/api/v1` API, with `/auth/login`.
+    V1,
+}
+
+impl Default for RouteLayout {
+    fn default() -> Self {
+        Self::Server
+    }
+}
+
+impl RouteLayout {
+    pub fn routes(self) -> Routes {
+        match self {
+            Self::Server => Routes::SERVER,
+            Self::V1 => Routes::V1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConnectionProfile {
+    /// The sidecar backend spawned and supervised by the app.
+    Managed,
+    Remote {
+        base_url: String,
+        #[serde(default)]
+        auth: RemoteAuth,
+        #[serde(default)]
+        routes: RouteLayout,
+    },
+}
+
+impl ConnectionProfile {
+    pub fn kind(&self) -> ProfileKind {
+        match self {
+            Self::Managed => ProfileKind::Managed,
+            Self::Remote { .. } => ProfileKind::Remote,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileKind {
+    Managed,
+    Remote,
+}
+
+/// The `connection` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionSettings {
+    pub active: String,
+    pub profiles: BTreeMap<String, ConnectionProfile>,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        let mut profiles = BTreeMap::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), ConnectionProfile::Managed);
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles,
+        }
+    }
+}
+
+impl ConnectionSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.profiles.contains_key(&self.active) {
+            return Err(format!("Unknown connection profile `{}`", self.active));
+        }
+        for (name, profile) in &self.profiles {
+            if let ConnectionProfile::Remote { base_url, .. } = profile {
+                let url = reqwest::Url::parse(base_url)
+                    .map_err(|e| format!("Profile `{}`: invalid base URL: {}", name, e))?;
+                if url.scheme() != "http" && url.scheme() != "https" {
+                    return Err(format!(
+                        "Profile `{}`: base URL must be http or https",
+                        name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn active_profile(&self) -> &ConnectionProfile {
+        self.profiles
+            .get(&self.active)
+            .unwrap_or(&ConnectionProfile::Managed)
+    }
+
+    /// Moves new credentials into the vault; credentials sent back without a
+    /// value keep the one saved for the same profile in `current`.
+    pub fn seal(&mut self, vault: &Vault, current: &ConnectionSettings) -> Result<(), String> {
+        for (name, profile) in self.profiles.iter_mut() {
+            let auth = match profile {
+                ConnectionProfile::Remote { auth, .. } => auth,
+                ConnectionProfile::Managed => continue,
+            };
+            let (suffix, credential) = match auth.credential_mut() {
+                Some(credential) => credential,
+                None => continue,
+            };
+            let saved = match current.profiles.get(name) {
+                Some(ConnectionProfile::Remote { auth, .. }) => auth.credential(),
+                _ => None,
+            };
+            credential
+                .seal(vault, credential_name(name, suffix), saved)
+                .map_err(|e| format!("Profile `{}`: {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// A copy for the webview, with credentials masked.
+    pub fn masked(&self, vault: &Vault) -> Self {
+        let mut masked = self.clone();
+        for profile in masked.profiles.values_mut() {
+            if let ConnectionProfile::Remote { auth, .. } = profile {
+                if let Some((_, credential)) = auth.credential_mut() {
+                    credential.mask(vault);
+                }
+            }
+        }
+        masked
+    }
+}
+
+/// Where and how to reach the backend selected by the active profile.
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub base_url: String,
+    pub auth: Auth,
+    pub routes: RouteLayout,
+}
+
+impl Connection {
+    pub fn managed(endpoint: &Endpoint) -> Self {
+        Self {
+            base_url: endpoint.base_url(),
+            auth: Auth::None,
+            routes: RouteLayout::Server,
+        }
+    }
+
+    /// SDK client builder for this connection, sharing the pool of `http`.
+    pub fn builder(&self, http: reqwest::Client) -> ClientBuilder {
+        let builder = Client::builder(self.base_url.clone())
+            .routes(self.routes.routes())
+            .http_client(http);
+        match &self.auth {
+            Auth::None => builder,
+            Auth::Bearer { token } => builder.token(Token::new(token.to_string())),
+            Auth::Basic { username, password } => {
+                builder.basic_auth(username.clone(), password.to_string())
+            }
+        }
+    }
+
+    pub fn client(&self, http: reqwest::Client) -> llm_verifier_client::Result<Client> {
+        self.builder(http).build()
+    }
+}
+
+/// Managed state holding the connection settings and the remote's health.
+pub struct Profiles {
+    settings: RwLock<ConnectionSettings>,
+    remote_health: Mutex<Option<HealthSnapshot>>,
+}
+
+impl Profiles {
+    /// Loads the settings saved in the desktop config.
+    pub fn load(app: &AppHandle) -> Self {
+        let settings = config::load(app)
+            .map(|config| config.connection)
+            .map_err(|e| e.to_string())
+            .and_then(|settings| settings.validate().map(|_| settings))
+            .unwrap_or_else(|e| {
+                eprintln!("Using the managed backend: {}", e);
+                ConnectionSettings::default()
+            });
+        Self {
+            settings: RwLock::new(settings),
+            remote_health: Mutex::new(None),
+        }
+    }
+
+    pub async fn active(&self) -> ConnectionProfile {
+        self.settings.read().await.active_profile().clone()
+    }
+
+    pub async fn active_name(&self) -> String {
+        self.settings.read().await.active.clone()
+    }
+
+    /// Resolves the active profile to a base URL, requiring a running
+    /// backend for the managed profile and an unlocked vault for remote
+    /// credentials.
+    pub async fn connection(
+        &self,
+        backend: &BackendProcess,
+        vault: &Vault,
+    ) -> Result<Connection, BackendError> {
+        match self.active().await {
+            ConnectionProfile::Managed => Ok(Connection::managed(&backend.endpoint().await?)),
+            ConnectionProfile::Remote {
+                base_url,
+                auth,
+                routes,
+            } => Ok(Connection {
+                base_url,
+                auth: auth.resolve(vault).map_err(BackendError::Vault)?,
+                routes,
+            }),
+        }
+    }
+
+    /// Moves the new credentials in `settings` into the vault before they
+    /// are saved.
+    pub async fn seal(
+        &self,
+        vault: &Vault,
+        settings: &mut ConnectionSettings,
+    ) -> Result<(), String> {
+        settings.seal(vault, &*self.settings.read().await)
+    }
+
+    /// Switches to saved `settings`. Leaving the managed profile stops the
+    /// local backend so it does not run unseen.
+    pub async fn adopt(
+        &self,
+        app: &AppHandle,
+        backend: &BackendProcess,
+        settings: ConnectionSettings,
+    ) -> Result<(), String> {
+        let (previous, active) = {
+            let mut current = self.settings.write().await;
+            let previous = current.active_profile().clone();
+            *current = settings;
+            (previous, current.active_profile().clone())
+        };
+        if previous != active {
+            *self.remote_health.lock().await = None;
+            if previous == ConnectionProfile::Managed {
+                match backend.stop().await {
+                    Ok(_) | Err(BackendError::NotRunning) => {}
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+        }
+
+        app.emit_all("backend://profile", active.kind())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn remote_health(&self) -> Option<HealthSnapshot> {
+        self.remote_health.lock().await.clone()
+    }
+
+    pub async fn record_remote_health(&self, snapshot: HealthSnapshot) {
+        *self.remote_health.lock().await = Some(snapshot);
+    }
+}
+
+/// The saved profiles, with credentials masked.
+#[tauri::command]
+pub async fn get_connection_profiles(
+    profiles: State<'_, Profiles>,
+    vault: State<'_, Vault>,
+) -> Result<ConnectionSettings, String> {
+    Ok(profiles.settings.read().await.masked(&vault))
+}
+
+/// Replaces the saved profiles and switches to `settings.active`. New
+/// credentials are stored in the vault, which must be unlocked.
+#[tauri::command]
+pub async fn save_connection_profiles(
+    app: AppHandle,
+    profiles: State<'_, Profiles>,
+    backend: State<'_, BackendProcess>,
+    vault: State<'_, Vault>,
+    mut settings: ConnectionSettings,
+) -> Result<String, String> {
+    settings.validate()?;
+    profiles.seal(&vault, &mut settings).await?;
+
+    config::update(&app, |config| config.connection = settings.clone())
+        .map_err(|e| e.to_string())?;
+    profiles.adopt(&app, &backend, settings.clone()).await?;
+    Ok(format!("Using connection profile `{}`", settings.active))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    fn scratch_vault(test: &str) -> Vault {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-profile-{}-{}",
+            std::process::id(),
+            test
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        Vault::at(Some(dir.join("vault.json")))
+    }
+
+    fn settings(auth: serde_json::Value) -> ConnectionSettings {
+        serde_json::from_value(json!({
+            "active": "shared team",
+            "profiles": {
+                "local": { "kind": "managed" },
+                "shared team": { "kind": "remote", "base_url": "https://verifier.example", "auth": auth }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn auth(settings: &ConnectionSettings) -> RemoteAuth {
+        match settings.active_profile() {
+            ConnectionProfile::Remote { auth, .. } => auth.clone(),
+            ConnectionProfile::Managed => panic!("expected a remote profile"),
+        }
+    }
+
+    #[test]
+    fn moves_new_credentials_into_the_vault() {
+        let vault = scratch_vault("seal");
+        vault.unlock(PASSPHRASE).unwrap();
+        let mut saved =
+            settings(json!({ "type": "bearer", "token": { "value": "tok-0123456789ab" } }));
+        saved.seal(&vault, &ConnectionSettings::default()).unwrap();
+
+        let name = "LLM_VERIFIER_REMOTE_SHARED_TEAM_TOKEN";
+        assert!(is_credential(name));
+        assert_eq!(*vault.secret(name).unwrap(), "tok-0123456789ab");
+        // Only the reference reaches the config.
+        let written = serde_json::to_value(&saved).unwrap();
+        assert_eq!(
+            written["profiles"]["shared team"]["auth"],
+            json!({ "type": "bearer", "token": { "secret": name } })
+        );
+        match auth(&saved).resolve(&vault).unwrap() {
+            Auth::Bearer { token } => assert_eq!(*token, "tok-0123456789ab"),
+            other => panic!("unexpected auth {:?}", other),
+        }
+
+        let masked = serde_json::to_value(saved.masked(&vault)).unwrap();
+        assert_eq!(
+            masked["profiles"]["shared team"]["auth"]["token"]["masked"],
+            json!("tok…89ab")
+        );
+        vault.lock();
+        let masked = serde_json::to_value(saved.masked(&vault)).unwrap();
+        assert_eq!(
+            masked["profiles"]["shared team"]["auth"]["token"]["masked"],
+            json!("••••••••")
+        );
+        assert!(matches!(
+            auth(&saved).resolve(&vault),
+            Err(VaultError::Locked)
+        ));
+    }
+
+    #[test]
+    fn keeps_saved_credentials_and_moves_plain_ones() {
+        let vault = scratch_vault("keep");
+        vault.unlock(PASSPHRASE).unwrap();
+        // Written before credentials moved to the vault.
+        let current = settings(json!({ "type": "basic", "username": "me", "password": "pw" }));
+
+        // The webview sends the profile back without a new value.
+        let mut saved = settings(json!({ "type": "basic", "username": "me", "password": {} }));
+        saved.seal(&vault, &current).unwrap();
+        let name = "LLM_VERIFIER_REMOTE_SHARED_TEAM_PASSWORD";
+        assert_eq!(*vault.secret(name).unwrap(), "pw");
+
+        let mut again = settings(json!({ "type": "basic", "username": "me", "password": {} }));
+        again.seal(&vault, &saved).unwrap();
+        assert_eq!(auth(&again), auth(&saved));
+
+        vault.lock();
+        let mut locked =
+            settings(json!({ "type": "basic", "username": "me", "password": { "value": "new" } }));
+        assert!(locked.seal(&vault, &saved).is_err());
+    }
+}
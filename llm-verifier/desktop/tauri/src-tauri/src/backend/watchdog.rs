@@ -0,0 +1,169 @@
+//! Restarts the backend with exponential backoff when it exits unexpectedly.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+use super::{BackendProcess, ExitInfo};
+use crate::config;
+
+/// How often the watchdog checks whether the backend is still alive.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A backend that stayed up this long is considered healthy again and the
+/// restart counter starts over.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// Crash recovery settings, read from the `restart` section of the desktop
+/// config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    pub enabled: bool,
+    pub max_restarts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_restarts: 5,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl RestartPolicy {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.restart)
+            .unwrap_or_default()
+    }
+
+    /// Delay before restart number `attempt` (1-based).
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        let ms = self.initial_backoff_ms.saturating_mul(factor);
+        Duration::from_millis(ms.min(self.max_backoff_ms))
+    }
+}
+
+/// Managed state holding the active restart policy.
+pub struct Watchdog(RwLock<RestartPolicy>);
+
+impl Watchdog {
+    pub fn load(app: &AppHandle) -> Self {
+        Self(RwLock::new(RestartPolicy::load(app)))
+    }
+
+    /// Adopts a changed policy; a restart already waiting keeps its delay.
+    pub async fn reload(&self, policy: RestartPolicy) {
+        *self.0.write().await = policy;
+    }
+}
+
+/// Payload of the `backend://crashed` event.
+#[derive(Debug, Clone, Serialize)]
+struct CrashedPayload {
+    exit: ExitInfo,
+    stderr: Vec<String>,
+    attempt: u32,
+    will_restart: bool,
+    retry_in_ms: Option<u64>,
+}
+
+/// Payload of the `backend://restarted` event.
+#[derive(Debug, Clone, Serialize)]
+struct RestartedPayload {
+    pid: u32,
+    attempt: u32,
+}
+
+/// Starts the watchdog loop for the lifetime of the app.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let backend = app.state::<BackendProcess>();
+        let watchdog = app.state::<Watchdog>();
+        let mut attempt = 0u32;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let mut crash = match backend.take_crash().await {
+                Some(crash) => crash,
+                None => continue,
+            };
+            if crash.uptime >= STABLE_UPTIME {
+                attempt = 0;
+            }
+
+            // A restart that fails to spawn counts as another crash.
+            loop {
+                let policy = watchdog.0.read().await.clone();
+                attempt += 1;
+                let will_restart = policy.enabled && attempt <= policy.max_restarts;
+                let delay = policy.backoff(attempt);
+
+                eprintln!(
+                    "Backend exited unexpectedly (code {:?}, signal {:?})",
+                    crash.exit.code, crash.exit.signal
+                );
+                let _ = app.emit_all(
+                    "backend://crashed",
+                    CrashedPayload {
+                        exit: crash.exit.clone(),
+                        stderr: crash.stderr.clone(),
+                        attempt,
+                        will_restart,
+                        retry_in_ms: will_restart.then(|| delay.as_millis() as u64),
+                    },
+                );
+                if !will_restart {
+                    attempt = 0;
+                    break;
+                }
+
+                tokio::time::sleep(delay).await;
+                match backend.restart_after(&crash).await {
+                    Ok(Some(pid)) => {
+                        println!("Backend restarted (pid {}, attempt {})", pid, attempt);
+                        let _ =
+                            app.emit_all("backend://restarted", RestartedPayload { pid, attempt });
+                        break;
+                    }
+                    // The user took over in the meantime.
+                    Ok(None) => {
+                        attempt = 0;
+                        break;
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to restart backend: {}", e);
+                        crash.stderr.push(e.to_string());
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_restart_policy(watchdog: State<'_, Watchdog>) -> Result<RestartPolicy, String> {
+    Ok(watchdog.0.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn set_restart_policy(
+    app: AppHandle,
+    policy: RestartPolicy,
+    watchdog: State<'_, Watchdog>,
+) -> Result<String, String> {
+    let saved = policy.clone();
+    config::update(&app, |config| config.restart = saved).map_err(|e| e.to_string())?;
+    watchdog.reload(policy).await;
+    Ok("Restart policy saved".to_string())
+}
@@ -0,0 +1,902 @@
+//! Line-based editing of block-style YAML such as the backend's
+//! `config.yaml`. Only the lines an edit touches are rewritten, so comments,
+//! key order, quoting and `${ENV}` placeholders elsewhere survive a save.
+//!
+//! Block scalars and values spanning several lines, such as a flow collection
+//! or a plain scalar continued on the next line, are treated as opaque: they
+//! can be replaced or removed as a whole but not edited inside.
+
+use serde_json::Value;
+
+/// Indentation used for lines this module writes.
+const INDENT: usize = 2;
+
+/// One step of a dotted path such as `llms[1].headers.User-Agent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Splits `global.api_key` or `llms[0].features.tool_calling` into segments.
+pub fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut rest) = match part.find('[') {
+            Some(i) => part.split_at(i),
+            None => (part, ""),
+        };
+        if key.is_empty() && (segments.is_empty() || rest.is_empty()) {
+            return Err(format!("Invalid config path `{}`", path));
+        }
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        }
+        while !rest.is_empty() {
+            let close = rest
+                .find(']')
+                .ok_or_else(|| format!("Invalid config path `{}`", path))?;
+            let index = rest[1..close]
+                .parse()
+                .map_err(|_| format!("Invalid index in config path `{}`", path))?;
+            segments.push(Segment::Index(index));
+            rest = &rest[close + 1..];
+            if !rest.is_empty() && !rest.starts_with('[') {
+                return Err(format!("Invalid config path `{}`", path));
+            }
+        }
+    }
+    Ok(segments)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    /// The `-` of a sequence item.
+    Dash,
+    Content,
+}
+
+/// A significant piece of a line: lines starting with `- key: value` yield a
+/// dash token and a content token.
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    line: usize,
+    /// Column of the token, in bytes.
+    col: usize,
+    kind: TokenKind,
+}
+
+#[derive(Debug)]
+enum Node {
+    /// A single-line scalar or flow collection at `line[start..end]`.
+    Scalar {
+        line: usize,
+        start: usize,
+        end: usize,
+    },
+    /// A block scalar (`|`, `>`) or a multi-line value; only replaced as a whole.
+    Opaque,
+    Map {
+        col: usize,
+        entries: Vec<(String, Entry)>,
+    },
+    Seq {
+        col: usize,
+        items: Vec<Entry>,
+    },
+    /// A key with no value.
+    Null,
+}
+
+#[derive(Debug)]
+struct Entry {
+    /// Line of the key or the item's dash.
+    first: usize,
+    /// Last line belonging to the value.
+    last: usize,
+    /// Column of the key or dash.
+    col: usize,
+    node: Node,
+}
+
+/// Byte offset where a trailing `# comment` starts, ignoring `#` inside quotes
+/// or not preceded by whitespace.
+fn comment_start(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut quote: Option<u8> = None;
+    for (i, &b) in bytes.iter().enumerate() {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if b == b'"' || b == b'\'' => quote = Some(b),
+            None if b == b'#' && (i == 0 || bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') => {
+                return Some(i)
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/// `text` without a trailing comment or whitespace.
+fn value_text(text: &str) -> &str {
+    match comment_start(text) {
+        Some(i) => text[..i].trim_end(),
+        None => text.trim_end(),
+    }
+}
+
+/// Splits `key: rest` and returns the key and the byte offset of `rest`.
+fn split_key(text: &str) -> Option<(String, usize)> {
+    let text_end = value_text(text).len();
+    let bytes = text.as_bytes();
+    let (key, after_key) = if bytes.first() == Some(&b'"') || bytes.first() == Some(&b'\'') {
+        let quote = bytes[0];
+        let close = text[1..].find(quote as char)? + 1;
+        (text[1..close].to_string(), close + 1)
+    } else {
+        if text.starts_with('[') || text.starts_with('{') {
+            return None;
+        }
+        let mut colon = None;
+        for (i, &b) in bytes[..text_end].iter().enumerate() {
+            if b == b':' && (i + 1 == text_end || bytes[i + 1] == b' ') {
+                colon = Some(i);
+                break;
+            }
+        }
+        let colon = colon?;
+        (text[..colon].trim_end().to_string(), colon)
+    };
+    if !text[after_key..].starts_with(':') {
+        return None;
+    }
+    let mut rest = after_key + 1;
+    while rest < text.len() && text.as_bytes()[rest] == b' ' {
+        rest += 1;
+    }
+    Some((key, rest))
+}
+
+struct Parser<'a> {
+    lines: &'a [String],
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(lines: &'a [String]) -> Self {
+        let mut tokens = Vec::new();
+        for (line, text) in lines.iter().enumerate() {
+            let trimmed = text.trim_start();
+            if trimmed.is_empty()
+                || trimmed.starts_with('#')
+                || trimmed == "---"
+                || trimmed == "..."
+            {
+                continue;
+            }
+            let mut col = text.len() - trimmed.len();
+            loop {
+                let rest = &text[col..];
+                if rest == "-" || rest.starts_with("- ") {
+                    tokens.push(Token {
+                        line,
+                        col,
+                        kind: TokenKind::Dash,
+                    });
+                    col += 1;
+                    while col < text.len() && text.as_bytes()[col] == b' ' {
+                        col += 1;
+                    }
+                    if col >= text.len() || text[col..].starts_with('#') {
+                        break;
+                    }
+                } else {
+                    tokens.push(Token {
+                        line,
+                        col,
+                        kind: TokenKind::Content,
+                    });
+                    break;
+                }
+            }
+        }
+        Self {
+            lines,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn text(&self, token: Token) -> &'a str {
+        &self.lines[token.line][token.col..]
+    }
+
+    /// Parses the node starting at the next token, if it is indented at
+    /// least `min_col`. Returns it with its last line.
+    fn node(&mut self, min_col: usize) -> Option<(Node, usize)> {
+        let token = self.peek().filter(|t| t.col >= min_col)?;
+        match token.kind {
+            TokenKind::Dash => Some(self.seq(token.col)),
+            TokenKind::Content if split_key(self.text(token)).is_some() => {
+                Some(self.map(token.col))
+            }
+            TokenKind::Content => {
+                self.pos += 1;
+                let end = token.col + value_text(self.text(token)).len();
+                Some((
+                    Node::Scalar {
+                        line: token.line,
+                        start: token.col,
+                        end,
+                    },
+                    token.line,
+                ))
+            }
+        }
+    }
+
+    /// Consumes the lines indented deeper than `col` that continue the
+    /// scalar `node` ending on `last`, making it opaque if there are any.
+    fn continuation(&mut self, col: usize, node: Node, last: usize) -> (Node, usize) {
+        let mut continued = last;
+        while let Some(next) = self.peek().filter(|t| t.col > col) {
+            continued = next.line;
+            self.pos += 1;
+        }
+        if continued == last {
+            (node, last)
+        } else {
+            (Node::Opaque, continued)
+        }
+    }
+
+    fn map(&mut self, col: usize) -> (Node, usize) {
+        let mut entries = Vec::new();
+        let mut last = 0;
+        while let Some(token) = self.peek() {
+            if token.kind != TokenKind::Content || token.col != col {
+                break;
+            }
+            let text = self.text(token);
+            let (key, rest) = match split_key(text) {
+                Some(split) => split,
+                None => break,
+            };
+            self.pos += 1;
+            let value = value_text(&text[rest..]);
+            let (node, entry_last) = if value.starts_with('|') || value.starts_with('>') {
+                self.continuation(col, Node::Opaque, token.line)
+            } else if !value.is_empty() {
+                let start = token.col + rest;
+                let scalar = Node::Scalar {
+                    line: token.line,
+                    start,
+                    end: start + value.len(),
+                };
+                self.continuation(col, scalar, token.line)
+            } else {
+                let nested = self
+                    .peek()
+                    .filter(|t| t.col > col || (t.kind == TokenKind::Dash && t.col == col));
+                match nested {
+                    Some(next) => self.node(next.col).unwrap_or((Node::Null, token.line)),
+                    None => (Node::Null, token.line),
+                }
+            };
+            last = entry_last;
+            entries.push((
+                key,
+                Entry {
+                    first: token.line,
+                    last: entry_last,
+                    col,
+                    node,
+                },
+            ));
+        }
+        (Node::Map { col, entries }, last)
+    }
+
+    fn seq(&mut self, col: usize) -> (Node, usize) {
+        let mut items = Vec::new();
+        let mut last = 0;
+        while let Some(dash) = self.peek() {
+            if dash.kind != TokenKind::Dash || dash.col != col {
+                break;
+            }
+            self.pos += 1;
+            let (node, item_last) = match self.peek() {
+                Some(next) if next.line == dash.line => {
+                    self.node(next.col).unwrap_or((Node::Null, dash.line))
+                }
+                Some(next) if next.col > col => {
+                    self.node(next.col).unwrap_or((Node::Null, dash.line))
+                }
+                _ => (Node::Null, dash.line),
+            };
+            let (node, item_last) = match node {
+                Node::Scalar { .. } => self.continuation(col, node, item_last),
+                node => (node, item_last),
+            };
+            last = item_last;
+            items.push(Entry {
+                first: dash.line,
+                last: item_last,
+                col,
+                node,
+            });
+        }
+        (Node::Seq { col, items }, last)
+    }
+}
+
+/// Whether `value` can be written without quotes and reads back as the same string.
+fn is_plain(value: &str) -> bool {
+    !value.is_empty()
+        && value.trim() == value
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.contains('\n')
+        && !value.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && matches!(
+            serde_yaml::from_str::<serde_yaml::Value>(value),
+            Ok(serde_yaml::Value::String(ref s)) if s == value
+        )
+}
+
+fn render_key(key: &str) -> String {
+    if is_plain(key) && !key.contains(':') {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+/// Renders a scalar, following the quoting style of `previous` when given.
+fn render_scalar(value: &Value, previous: Option<&str>) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::String(s) => match previous.and_then(|p| p.chars().next()) {
+            Some('\'') if !s.contains('\n') => format!("'{}'", s.replace('\'', "''")),
+            Some('"') => value.to_string(),
+            _ if is_plain(s) => s.clone(),
+            _ => value.to_string(),
+        },
+        Value::Array(items) if items.is_empty() => "[]".to_string(),
+        Value::Object(object) if object.is_empty() => "{}".to_string(),
+        // Callers render non-empty collections as blocks.
+        _ => value.to_string(),
+    }
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(object) => !object.is_empty(),
+        _ => false,
+    }
+}
+
+/// Renders the body of a block collection at `col`.
+fn render_block(value: &Value, col: usize, out: &mut Vec<String>) {
+    let pad = " ".repeat(col);
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                render_entry(&pad, &render_key(key), value, col, out);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                render_item(&pad, item, col, out);
+            }
+        }
+        _ => out.push(format!("{}{}", pad, render_scalar(value, None))),
+    }
+}
+
+/// Renders `key: value` whose first line starts with `prefix`; nested lines
+/// are indented relative to `col`.
+fn render_entry(prefix: &str, key: &str, value: &Value, col: usize, out: &mut Vec<String>) {
+    if is_block(value) {
+        out.push(format!("{}{}:", prefix, key));
+        render_block(value, col + INDENT, out);
+    } else {
+        out.push(format!("{}{}: {}", prefix, key, render_scalar(value, None)));
+    }
+}
+
+/// Renders a sequence item whose dash is at `col`, its first line starting
+/// with `prefix`.
+fn render_item(prefix: &str, value: &Value, col: usize, out: &mut Vec<String>) {
+    if !is_block(value) {
+        out.push(format!("{}- {}", prefix, render_scalar(value, None)));
+        return;
+    }
+    let start = out.len();
+    render_block(value, col + INDENT, out);
+    // Pull the first nested line up onto the dash line.
+    let first = out[start].split_off(col + INDENT);
+    out[start] = format!("{}- {}", prefix, first);
+}
+
+/// Wraps `value` in the objects and arrays that `rest` describes.
+fn nest(rest: &[Segment], value: Value) -> Result<Value, String> {
+    let mut value = value;
+    for segment in rest.iter().rev() {
+        value = match segment {
+            Segment::Key(key) => {
+                let mut object = serde_json::Map::new();
+                object.insert(key.clone(), value);
+                Value::Object(object)
+            }
+            Segment::Index(0) => Value::Array(vec![value]),
+            Segment::Index(i) => {
+                return Err(format!("Cannot create item {} of a missing list", i));
+            }
+        };
+    }
+    Ok(value)
+}
+
+fn describe(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            Segment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+/// Where an edit lands in the parsed tree.
+enum Target<'n> {
+    Found(&'n Entry),
+    /// The first `depth` segments exist; the rest must be created.
+    Missing {
+        /// Collection that gets the new entry; `None` for an empty document.
+        parent: Option<&'n Node>,
+        /// Entry owning a `Null` value that the new value replaces.
+        null_owner: Option<&'n Entry>,
+        depth: usize,
+    },
+}
+
+fn locate<'n>(root: Option<&'n Node>, path: &[Segment]) -> Result<Target<'n>, String> {
+    let mut node = match root {
+        Some(node) => node,
+        None => {
+            return Ok(Target::Missing {
+                parent: None,
+                null_owner: None,
+                depth: 0,
+            })
+        }
+    };
+    let mut owner: Option<&Entry> = None;
+    for (depth, segment) in path.iter().enumerate() {
+        let next = match (node, segment) {
+            (Node::Map { entries, .. }, Segment::Key(key)) => {
+                entries.iter().find(|(k, _)| k == key).map(|(_, e)| e)
+            }
+            (Node::Seq { items, .. }, Segment::Index(i)) => items.get(*i),
+            (Node::Null, _) => {
+                return Ok(Target::Missing {
+                    parent: None,
+                    null_owner: owner,
+                    depth,
+                })
+            }
+            (Node::Map { .. }, Segment::Index(_)) => {
+                return Err(format!(
+                    "`{}` is a mapping, not a list",
+                    describe(&path[..depth])
+                ))
+            }
+            (Node::Seq { .. }, Segment::Key(_)) => {
+                return Err(format!(
+                    "`{}` is a list, not a mapping",
+                    describe(&path[..depth])
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "`{}` is a single value and has no `{}`",
+                    describe(&path[..depth]),
+                    describe(&path[depth..=depth])
+                ))
+            }
+        };
+        match next {
+            Some(entry) => {
+                owner = Some(entry);
+                node = &entry.node;
+            }
+            None => {
+                return Ok(Target::Missing {
+                    parent: Some(node),
+                    null_owner: None,
+                    depth,
+                })
+            }
+        }
+    }
+    owner
+        .map(Target::Found)
+        .ok_or_else(|| "Empty config path".to_string())
+}
+
+/// A YAML document being edited.
+pub struct Document {
+    lines: Vec<String>,
+    trailing_newline: bool,
+}
+
+impl Document {
+    pub fn parse(text: &str) -> Self {
+        let trailing_newline = text.ends_with('\n') || text.is_empty();
+        let lines = text
+            .lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect();
+        Self {
+            lines,
+            trailing_newline,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = self.lines.join("\n");
+        if self.trailing_newline && !self.lines.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+
+    fn root(&self) -> Option<Node> {
+        Parser::new(&self.lines).node(0).map(|(node, _)| node)
+    }
+
+    /// Text before the key or dash of `entry` on its first line, e.g. the
+    /// `- ` of the first key of a list item.
+    fn prefix(&self, entry: &Entry) -> String {
+        self.lines[entry.first][..entry.col].to_string()
+    }
+
+    /// Sets the value at `path`, creating missing mappings and list items.
+    pub fn set(&mut self, path: &[Segment], value: &Value) -> Result<(), String> {
+        let root = self.root();
+        let (first, last, replacement) = match locate(root.as_ref(), path)? {
+            Target::Found(entry) => {
+                if let (Node::Scalar { line, start, end }, false) = (&entry.node, is_block(value)) {
+                    let line = &mut self.lines[*line];
+                    let scalar = render_scalar(value, Some(&line[*start..*end]));
+                    line.replace_range(*start..*end, &scalar);
+                    return Ok(());
+                }
+                let mut out = Vec::new();
+                let prefix = self.prefix(entry);
+                match path.last() {
+                    Some(Segment::Key(key)) => {
+                        render_entry(&prefix, &render_key(key), value, entry.col, &mut out)
+                    }
+                    _ => render_item(&prefix, value, entry.col, &mut out),
+                }
+                (entry.first, entry.last + 1, out)
+            }
+            Target::Missing {
+                null_owner: Some(owner),
+                depth,
+                ..
+            } => {
+                let nested = nest(&path[depth..], value.clone())?;
+                let mut out = Vec::new();
+                let prefix = self.prefix(owner);
+                match &path[depth - 1] {
+                    Segment::Key(key) => {
+                        render_entry(&prefix, &render_key(key), &nested, owner.col, &mut out)
+                    }
+                    Segment::Index(_) => render_item(&prefix, &nested, owner.col, &mut out),
+                }
+                (owner.first, owner.last + 1, out)
+            }
+            Target::Missing { parent, depth, .. } => {
+                let mut out = Vec::new();
+                let at = match parent {
+                    Some(Node::Map { col, entries }) => {
+                        let key = match &path[depth] {
+                            Segment::Key(key) => key,
+                            Segment::Index(_) => unreachable!("checked by locate"),
+                        };
+                        let nested = nest(&path[depth + 1..], value.clone())?;
+                        render_entry(&" ".repeat(*col), &render_key(key), &nested, *col, &mut out);
+                        entries.last().map_or(self.lines.len(), |(_, e)| e.last + 1)
+                    }
+                    Some(Node::Seq { col, items }) => {
+                        match path[depth] {
+                            Segment::Index(i) if i == items.len() => {}
+                            Segment::Index(i) => {
+                                return Err(format!(
+                                    "`{}` has {} items; cannot set item {}",
+                                    describe(&path[..depth]),
+                                    items.len(),
+                                    i
+                                ))
+                            }
+                            Segment::Key(_) => unreachable!("checked by locate"),
+                        }
+                        let nested = nest(&path[depth + 1..], value.clone())?;
+                        render_item(&" ".repeat(*col), &nested, *col, &mut out);
+                        items.last().map_or(self.lines.len(), |e| e.last + 1)
+                    }
+                    _ => {
+                        // Empty document.
+                        let nested = nest(path, value.clone())?;
+                        render_block(&nested, 0, &mut out);
+                        self.lines.len()
+                    }
+                };
+                (at, at, out)
+            }
+        };
+        self.lines.splice(first..last, replacement);
+        Ok(())
+    }
+
+    /// Removes the entry or list item at `path`; a no-op when it does not exist.
+    pub fn remove(&mut self, path: &[Segment]) -> Result<(), String> {
+        let root = self.root();
+        let entry = match locate(root.as_ref(), path)? {
+            Target::Found(entry) => entry,
+            Target::Missing { .. } => return Ok(()),
+        };
+        let prefix = self.prefix(entry);
+        let (first, last) = (entry.first, entry.last);
+        // A key sharing its line with a list item's dash hands the dash to
+        // the next key, or leaves an empty mapping behind.
+        let inherits_dash = prefix.trim_start().starts_with('-');
+        if inherits_dash && matches!(path.last(), Some(Segment::Key(_))) {
+            let next = self
+                .lines
+                .iter()
+                .enumerate()
+                .skip(last + 1)
+                .find(|(_, line)| {
+                    let trimmed = line.trim_start();
+                    !trimmed.is_empty() && !trimmed.starts_with('#')
+                })
+                .map(|(i, line)| (i, line.len() - line.trim_start().len()));
+            match next {
+                Some((i, indent)) if indent == entry.col => {
+                    let moved = format!("{}{}", prefix, &self.lines[i][indent..]);
+                    self.lines[i] = moved;
+                    self.lines.drain(first..i);
+                }
+                _ => {
+                    self.lines
+                        .splice(first..=last, vec![format!("{}{{}}", prefix)]);
+                }
+            }
+            return Ok(());
+        }
+        self.lines.drain(first..=last);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const EXAMPLE: &str = include_str!("../../../../../config.yaml.example");
+
+    fn values(text: &str) -> Value {
+        serde_yaml::from_str(text).expect("edited YAML parses")
+    }
+
+    fn set(text: &str, path: &str, value: Value) -> String {
+        let mut document = Document::parse(text);
+        document
+            .set(&parse_path(path).unwrap(), &value)
+            .expect("set succeeds");
+        document.text()
+    }
+
+    fn remove(text: &str, path: &str) -> String {
+        let mut document = Document::parse(text);
+        document
+            .remove(&parse_path(path).unwrap())
+            .expect("remove succeeds");
+        document.text()
+    }
+
+    #[test]
+    fn parses_paths() {
+        assert_eq!(
+            parse_path("llms[1].headers.User-Agent").unwrap(),
+            vec![
+                Segment::Key("llms".to_string()),
+                Segment::Index(1),
+                Segment::Key("headers".to_string()),
+                Segment::Key("User-Agent".to_string()),
+            ]
+        );
+        assert!(parse_path("").is_err());
+        assert!(parse_path("a..b").is_err());
+        assert!(parse_path("llms[x]").is_err());
+        assert!(parse_path("llms[0]x").is_err());
+    }
+
+    #[test]
+    fn round_trips_unchanged() {
+        assert_eq!(Document::parse(EXAMPLE).text(), EXAMPLE);
+        assert_eq!(Document::parse("a: 1\n").text(), "a: 1\n");
+    }
+
+    #[test]
+    fn sets_map_key_keeping_comment_and_quotes() {
+        let text = set(EXAMPLE, "global.api_key", json!("${ANTHROPIC_API_KEY}"));
+        assert!(text.contains("  api_key: \"${ANTHROPIC_API_KEY}\" # Use environment variable\n"));
+        assert_eq!(text.lines().count(), EXAMPLE.lines().count());
+
+        let mut expected = values(EXAMPLE);
+        expected["global"]["api_key"] = json!("${ANTHROPIC_API_KEY}");
+        assert_eq!(values(&text), expected);
+    }
+
+    #[test]
+    fn sets_list_item_field() {
+        let text = set(EXAMPLE, "llms[1].model", json!("gpt-4o-mini"));
+        assert!(text.contains("     model: \"gpt-4o-mini\"\n"));
+        let mut expected = values(EXAMPLE);
+        expected["llms"][1]["model"] = json!("gpt-4o-mini");
+        assert_eq!(values(&text), expected);
+    }
+
+    #[test]
+    fn replaces_scalar_with_block() {
+        let text = set(EXAMPLE, "api.port", json!({ "http": 8080 }));
+        let mut expected = values(EXAMPLE);
+        expected["api"]["port"] = json!({ "http": 8080 });
+        assert_eq!(values(&text), expected);
+    }
+
+    #[test]
+    fn removes_map_key() {
+        let text = remove(EXAMPLE, "database.encryption_key");
+        assert!(!text.contains("encryption_key"));
+        let mut expected = values(EXAMPLE);
+        expected["database"]
+            .as_object_mut()
+            .unwrap()
+            .remove("encryption_key");
+        assert_eq!(values(&text), expected);
+        // Everything else, comments included, is untouched.
+        assert_eq!(text.lines().count(), EXAMPLE.lines().count() - 1);
+        assert!(text.contains("# If no LLMs are specified"));
+    }
+
+    #[test]
+    fn removes_list_item() {
+        let text = remove(EXAMPLE, "llms[0]");
+        let mut expected = values(EXAMPLE);
+        expected["llms"].as_array_mut().unwrap().remove(0);
+        assert_eq!(values(&text), expected);
+    }
+
+    #[test]
+    fn removing_first_key_of_item_hands_dash_to_next_key() {
+        let text = remove(EXAMPLE, "llms[0].name");
+        assert!(text.contains("   - endpoint: \"https://api.openai.com/v1\"\n"));
+        let mut expected = values(EXAMPLE);
+        expected["llms"][0].as_object_mut().unwrap().remove("name");
+        assert_eq!(values(&text), expected);
+    }
+
+    #[test]
+    fn removing_only_key_of_item_leaves_empty_mapping() {
+        let text = remove("list:\n  - a: 1\n  - b: 2\n", "list[0].a");
+        assert_eq!(text, "list:\n  - {}\n  - b: 2\n");
+    }
+
+    #[test]
+    fn removing_missing_path_is_a_no_op() {
+        assert_eq!(remove(EXAMPLE, "monitoring.enabled"), EXAMPLE);
+    }
+
+    #[test]
+    fn creates_values_under_null_key() {
+        let text = set("a:\nb: 1\n", "a.c", json!(2));
+        assert_eq!(text, "a:\n  c: 2\nb: 1\n");
+        assert_eq!(values(&text), json!({ "a": { "c": 2 }, "b": 1 }));
+
+        let text = set("a:\nb: 1\n", "a[0]", json!("x"));
+        assert_eq!(values(&text), json!({ "a": ["x"], "b": 1 }));
+    }
+
+    #[test]
+    fn creates_missing_keys_and_items() {
+        let text = set(EXAMPLE, "monitoring.health_port", json!("8086"));
+        let mut expected = values(EXAMPLE);
+        expected["monitoring"] = json!({ "health_port": "8086" });
+        assert_eq!(values(&text), expected);
+
+        let item = json!({ "name": "Local", "endpoint": "http://localhost:11434/v1" });
+        let text = set(EXAMPLE, "llms[2]", item.clone());
+        let mut expected = values(EXAMPLE);
+        expected["llms"].as_array_mut().unwrap().push(item);
+        assert_eq!(values(&text), expected);
+
+        let mut document = Document::parse(EXAMPLE);
+        assert!(document
+            .set(&parse_path("llms[5]").unwrap(), &json!("x"))
+            .is_err());
+    }
+
+    #[test]
+    fn creates_document_from_empty_text() {
+        let text = set("", "api.port", json!("9090"));
+        assert_eq!(values(&text), json!({ "api": { "port": "9090" } }));
+    }
+
+    #[test]
+    fn rejects_paths_through_scalars() {
+        let mut document = Document::parse(EXAMPLE);
+        assert!(document
+            .set(&parse_path("concurrency.max").unwrap(), &json!(1))
+            .is_err());
+        assert!(document
+            .set(&parse_path("global[0]").unwrap(), &json!(1))
+            .is_err());
+    }
+
+    #[test]
+    fn replaces_multi_line_flow_value_as_a_whole() {
+        let original = "features: [x,\n    y]\nother: 1\n";
+        assert_eq!(
+            set(original, "features", json!("z")),
+            "features: z\nother: 1\n"
+        );
+        assert_eq!(
+            values(&set(original, "features", json!(["z"]))),
+            json!({ "features": ["z"], "other": 1 })
+        );
+        assert_eq!(remove(original, "features"), "other: 1\n");
+
+        let item = "list:\n  - [a,\n     b]\n  - c\n";
+        assert_eq!(remove(item, "list[0]"), "list:\n  - c\n");
+    }
+
+    #[test]
+    fn replaces_multi_line_plain_scalar_as_a_whole() {
+        let original = "a: hello\n  world\nb: 1\n";
+        assert_eq!(values(original), json!({ "a": "hello world", "b": 1 }));
+        let text = set(original, "a", json!("x"));
+        assert_eq!(text, "a: x\nb: 1\n");
+        assert_eq!(remove(original, "a"), "b: 1\n");
+    }
+
+    #[test]
+    fn quotes_strings_that_would_change_type() {
+        let text = set("a: x\n", "a", json!("true"));
+        assert_eq!(values(&text), json!({ "a": "true" }));
+        let text = set("a: x\n", "a", json!("8080"));
+        assert_eq!(values(&text), json!({ "a": "8080" }));
+        let text = set("a: 'x'\n", "a", json!("it's"));
+        assert_eq!(text, "a: 'it''s'\n");
+    }
+}
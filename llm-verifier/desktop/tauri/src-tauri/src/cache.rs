@@ -0,0 +1,318 @@
+//! Cache of the model and provider lists. Entries are revalidated with
+//! `ETag`/`Last-Modified` once their TTL passes, and persisted to the app
+//! data dir so the last copy can be shown while the backend is unreachable
+//! or failing.
+
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use llm_verifier_client::{Conditional, Validators};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::api::ApiError;
+use crate::backend::BackendError;
+use crate::config;
+
+const SNAPSHOT_FILE_NAME: &str = "snapshot.json";
+
+/// Cache settings, read from the `cache` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheOptions {
+    /// How long a list is served without asking the backend.
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self { ttl_secs: 30 }
+    }
+}
+
+impl CacheOptions {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.cache)
+            .unwrap_or_default()
+    }
+}
+
+/// A cached list as returned to the webview.
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedList<T> {
+    pub items: Vec<T>,
+    /// When the backend last confirmed these items.
+    pub fetched_at: DateTime<Utc>,
+    /// Served from the snapshot because the backend could not be reached.
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    items: serde_json::Value,
+    validators: Validators,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Managed state holding the cached lists, keyed by profile, resource and query.
+pub struct ResponseCache {
+    entries: Mutex<HashMap<String, Entry>>,
+    path: Option<PathBuf>,
+    ttl_secs: AtomicI64,
+    /// Numbers the snapshots so a slow write cannot replace a newer one.
+    snapshot_seq: AtomicU64,
+    written_seq: Arc<std::sync::Mutex<u64>>,
+}
+
+impl ResponseCache {
+    /// Loads the snapshot saved by the previous run, if any.
+    pub fn load(app: &AppHandle) -> Self {
+        let options = CacheOptions::load(app);
+        let path = app
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(SNAPSHOT_FILE_NAME));
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| match serde_json::from_str(&contents) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    eprintln!("Ignoring unreadable cache snapshot: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self::new(entries, path, options.ttl_secs)
+    }
+
+    fn new(entries: HashMap<String, Entry>, path: Option<PathBuf>, ttl_secs: u64) -> Self {
+        Self {
+            entries: Mutex::new(entries),
+            path,
+            ttl_secs: AtomicI64::new(Self::ttl(ttl_secs)),
+            snapshot_seq: AtomicU64::new(0),
+            written_seq: Arc::new(std::sync::Mutex::new(0)),
+        }
+    }
+
+    fn ttl(secs: u64) -> i64 {
+        i64::try_from(secs).unwrap_or(i64::MAX)
+    }
+
+    /// Adopts a changed time to live; cached entries are kept.
+    pub fn reload(&self, options: &CacheOptions) {
+        self.ttl_secs
+            .store(Self::ttl(options.ttl_secs), Ordering::Relaxed);
+    }
+
+    /// Serves `key` from the cache while it is fresh, otherwise revalidates it
+    /// with `fetch`. Falls back to the cached copy, marked stale, when the
+    /// backend cannot be reached or answers with a server error.
+    pub async fn list<T, F, Fut>(&self, key: String, fetch: F) -> Result<CachedList<T>, ApiError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(Validators) -> Fut,
+        Fut: Future<Output = Result<Conditional<Vec<T>>, ApiError>>,
+    {
+        let cached = self.entries.lock().await.get(&key).cloned();
+        if let Some(entry) = &cached {
+            if (Utc::now() - entry.fetched_at).num_seconds() < self.ttl_secs.load(Ordering::Relaxed)
+            {
+                if let Ok(items) = serde_json::from_value(entry.items.clone()) {
+                    return Ok(CachedList {
+                        items,
+                        fetched_at: entry.fetched_at,
+                        stale: false,
+                    });
+                }
+            }
+        }
+
+        let validators = cached
+            .as_ref()
+            .map(|entry| entry.validators.clone())
+            .unwrap_or_default();
+        match fetch(validators).await {
+            Ok(Conditional::Modified { value, validators }) => {
+                let entry = Entry {
+                    items: serde_json::to_value(&value).unwrap_or_default(),
+                    validators,
+                    fetched_at: Utc::now(),
+                };
+                let fetched_at = entry.fetched_at;
+                self.store(key, entry).await;
+                Ok(CachedList {
+                    items: value,
+                    fetched_at,
+                    stale: false,
+                })
+            }
+            Ok(Conditional::NotModified) => {
+                // Only expected when validators were sent, i.e. for a cached entry.
+                let mut entry = cached.ok_or_else(|| {
+                    ApiError::Cache("The backend answered 304 for an uncached list".to_string())
+                })?;
+                entry.fetched_at = Utc::now();
+                let fetched_at = entry.fetched_at;
+                let items = serde_json::from_value(entry.items.clone())
+                    .map_err(|e| ApiError::Cache(e.to_string()))?;
+                self.store(key, entry).await;
+                Ok(CachedList {
+                    items,
+                    fetched_at,
+                    stale: false,
+                })
+            }
+            Err(e) if is_unreachable(&e) => match cached {
+                Some(entry) => match serde_json::from_value(entry.items) {
+                    Ok(items) => Ok(CachedList {
+                        items,
+                        fetched_at: entry.fetched_at,
+                        stale: true,
+                    }),
+                    Err(_) => Err(e),
+                },
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Adds `entry` and saves the snapshot off the async runtime.
+    async fn store(&self, key: String, entry: Entry) {
+        let (contents, seq) = {
+            let mut entries = self.entries.lock().await;
+            entries.insert(key, entry);
+            let contents = serde_json::to_vec(&*entries).unwrap_or_default();
+            (
+                contents,
+                self.snapshot_seq.fetch_add(1, Ordering::SeqCst) + 1,
+            )
+        };
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        let written_seq = self.written_seq.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let mut written = written_seq.lock().unwrap_or_else(|e| e.into_inner());
+            if *written > seq {
+                return Ok(());
+            }
+            config::write_atomic(&path, &contents)?;
+            *written = seq;
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result: std::io::Result<()>| result.map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to write cache snapshot: {}", e);
+        }
+    }
+}
+
+/// Whether `error` means the backend is down or failing rather than that it
+/// refused the request.
+fn is_unreachable(error: &ApiError) -> bool {
+    match error {
+        ApiError::Backend(BackendError::NotRunning)
+        | ApiError::Client(llm_verifier_client::Error::Transport { .. }) => true,
+        ApiError::Client(e) => e.status().map_or(false, |status| status.is_server_error()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn scratch_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-cache-{}-{}",
+            std::process::id(),
+            test
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(SNAPSHOT_FILE_NAME)
+    }
+
+    fn entry(items: serde_json::Value) -> Entry {
+        Entry {
+            items,
+            validators: Validators {
+                etag: Some("\"v1\"".to_string()),
+                last_modified: None,
+            },
+            fetched_at: Utc::now() - chrono::Duration::hours(1),
+        }
+    }
+
+    fn server_error() -> ApiError {
+        ApiError::Client(llm_verifier_client::Error::Status {
+            url: "http://127.0.0.1/api/models".to_string(),
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            body: "Failed to list models".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn not_modified_without_entry_is_an_error() {
+        let cache = ResponseCache::new(HashMap::new(), None, 30);
+        let result = cache
+            .list::<i64, _, _>("models".to_string(), |_| async {
+                Ok(Conditional::NotModified)
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::Cache(_))));
+    }
+
+    #[tokio::test]
+    async fn server_errors_fall_back_to_the_cached_copy() {
+        let mut entries = HashMap::new();
+        entries.insert("models".to_string(), entry(serde_json::json!([1, 2])));
+        let cache = ResponseCache::new(entries, None, 30);
+
+        let list = cache
+            .list::<i64, _, _>("models".to_string(), |_| async { Err(server_error()) })
+            .await
+            .unwrap();
+        assert_eq!(list.items, vec![1, 2]);
+        assert!(list.stale);
+
+        let result = cache
+            .list::<i64, _, _>("providers".to_string(), |_| async { Err(server_error()) })
+            .await;
+        assert!(matches!(result, Err(ApiError::Client(_))));
+    }
+
+    #[tokio::test]
+    async fn saves_the_snapshot() {
+        let path = scratch_path("snapshot");
+        let cache = ResponseCache::new(HashMap::new(), Some(path.clone()), 30);
+        cache
+            .list::<i64, _, _>("models".to_string(), |_| async {
+                Ok(Conditional::Modified {
+                    value: vec![3],
+                    validators: Validators::default(),
+                })
+            })
+            .await
+            .unwrap();
+
+        let saved: HashMap<String, Entry> =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["models"].items, serde_json::json!([3]));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
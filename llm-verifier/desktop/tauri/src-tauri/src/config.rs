@@ -0,0 +1,609 @@
+//! Desktop configuration stored in Tauri's app config directory.
+//!
+//! The file is a versioned [`DesktopConfig`]. Older files are migrated
+//! forward on load, every save is validated, and writes go through a temp
+//! file so a crash never leaves a truncated config behind.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use tauri::{AppHandle, Manager};
+
+use crate::backend::launch::LaunchOptions;
+use crate::backend::logs::LogFileOptions;
+use crate::backend::profile::ConnectionSettings;
+use crate::backend::watchdog::RestartPolicy;
+use crate::cache::CacheOptions;
+use crate::events::EventSettings;
+use crate::journal::JournalOptions;
+use crate::notifications::NotificationSettings;
+use crate::shutdown::ShutdownOptions;
+use crate::tray::TrayOptions;
+use crate::verification::VerificationOptions;
+use crate::watcher::OwnWrites;
+use crate::workspace::WorkspaceSettings;
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Schema version written by this build.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Forward migrations; `MIGRATIONS[n]` turns a version `n` file into version `n + 1`.
+const MIGRATIONS: &[fn(&mut serde_json::Map<String, serde_json::Value>)] = &[
+    // Unversioned files predate the schema but already use the v1 layout.
+    |_| {},
+];
+
+/// Replaces secrets in [`redact`]ed output.
+const REDACTED: &str = "[redacted]";
+
+/// Serializes config reads and writes, so an [`update`] cannot lose a change
+/// made concurrently and shutdown can wait for the write in flight.
+#[derive(Default)]
+pub struct PendingWrites(Mutex<()>);
+
+fn lock(app: &AppHandle) -> MutexGuard<'_, ()> {
+    app.state::<PendingWrites>()
+        .inner()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// The whole desktop config. Each section falls back to its defaults when
+/// missing.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopConfig {
+    pub version: u32,
+    pub backend: LaunchOptions,
+    pub log_file: LogFileOptions,
+    pub connection: ConnectionSettings,
+    pub restart: RestartPolicy,
+    pub shutdown: ShutdownOptions,
+    pub cache: CacheOptions,
+    pub events: EventSettings,
+    pub notifications: NotificationSettings,
+    pub journal: JournalOptions,
+    pub tray: TrayOptions,
+    pub verification: VerificationOptions,
+    pub workspaces: WorkspaceSettings,
+    /// Keys the app does not know, such as frontend preferences; kept as is.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for DesktopConfig {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            backend: LaunchOptions::default(),
+            log_file: LogFileOptions::default(),
+            connection: ConnectionSettings::default(),
+            restart: RestartPolicy::default(),
+            shutdown: ShutdownOptions::default(),
+            cache: CacheOptions::default(),
+            events: EventSettings::default(),
+            notifications: NotificationSettings::default(),
+            journal: JournalOptions::default(),
+            tray: TrayOptions::default(),
+            verification: VerificationOptions::default(),
+            workspaces: WorkspaceSettings::default(),
+            other: serde_json::Map::new(),
+        }
+    }
+}
+
+// Hand-written so secrets never reach a log through `{:?}`.
+impl fmt::Debug for DesktopConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(value) => write!(f, "DesktopConfig {}", redact(&value)),
+            Err(_) => f.write_str("DesktopConfig { .. }"),
+        }
+    }
+}
+
+/// A config value that failed validation.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// Dotted path of the offending value, e.g. `backend.port`.
+    pub field: String,
+    pub message: String,
+}
+
+impl DesktopConfig {
+    /// Every invalid field; empty when the config can be saved.
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let mut check = |field: String, result: Result<(), String>| {
+            if let Err(message) = result {
+                errors.push(FieldError { field, message });
+            }
+        };
+
+        if self.version != CURRENT_VERSION {
+            check(
+                "version".to_string(),
+                Err(format!("Expected version {}", CURRENT_VERSION)),
+            );
+        }
+
+        let backend = &self.backend;
+        if backend.subcommand.trim().is_empty() {
+            check(
+                "backend.subcommand".to_string(),
+                Err("Must not be empty".to_string()),
+            );
+        }
+        if backend.port == Some(0) {
+            check(
+                "backend.port".to_string(),
+                Err("Must be between 1 and 65535; leave unset to pick a free port".to_string()),
+            );
+        }
+        if backend.ready_timeout_secs == 0 {
+            check(
+                "backend.ready_timeout_secs".to_string(),
+                Err("Must be at least 1 second".to_string()),
+            );
+        }
+        for name in backend.env.keys() {
+            if name.is_empty() || name.contains('=') || name.contains('\0') {
+                check(
+                    format!("backend.env.{}", name),
+                    Err("Not a valid environment variable name".to_string()),
+                );
+            }
+        }
+
+        check("log_file".to_string(), self.log_file.validate());
+        check("connection".to_string(), self.connection.validate());
+        if self.restart.max_backoff_ms < self.restart.initial_backoff_ms {
+            check(
+                "restart.max_backoff_ms".to_string(),
+                Err("Must be at least the initial backoff".to_string()),
+            );
+        }
+        check("events.url".to_string(), self.events.validate());
+        check("workspaces".to_string(), self.workspaces.validate());
+        for (i, rule) in self.notifications.rules.iter().enumerate() {
+            check(format!("notifications.rules[{}]", i), rule.validate());
+        }
+        if self.journal.max_entries == 0 {
+            check(
+                "journal.max_entries".to_string(),
+                Err("Must keep at least one entry".to_string()),
+            );
+        }
+        if self.journal.max_age_days <= 0 {
+            check(
+                "journal.max_age_days".to_string(),
+                Err("Must be at least 1 day".to_string()),
+            );
+        }
+        if self.verification.poll_interval_secs == 0 {
+            check(
+                "verification.poll_interval_secs".to_string(),
+                Err("Must be at least 1 second".to_string()),
+            );
+        }
+        if self.verification.model_timeout_secs < self.verification.poll_interval_secs {
+            check(
+                "verification.model_timeout_secs".to_string(),
+                Err("Must be at least the poll interval".to_string()),
+            );
+        }
+        errors
+    }
+}
+
+/// Errors surfaced by the config commands.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("{0}")]
+    Io(String),
+    #[error("Failed to parse config: {0}")]
+    Parse(String),
+    #[error(
+        "Config version {found} was written by a newer app (this build reads up to {supported})"
+    )]
+    UnsupportedVersion { found: u64, supported: u32 },
+    #[error("Invalid config: {}", describe_fields(.0))]
+    Invalid(Vec<FieldError>),
+}
+
+impl ConfigError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::Parse(_) => "parse",
+            Self::UnsupportedVersion { .. } => "unsupported_version",
+            Self::Invalid(_) => "invalid",
+        }
+    }
+}
+
+// Commands hand errors to the webview as `{ kind, message, details }`.
+impl Serialize for ConfigError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::Invalid(fields) => map.serialize_entry("details", fields)?,
+            _ => map.serialize_entry("details", &())?,
+        }
+        map.end()
+    }
+}
+
+/// Joins field errors into one line for an error message.
+pub(crate) fn describe_fields(fields: &[FieldError]) -> String {
+    fields
+        .iter()
+        .map(|f| format!("{}: {}", f.field, f.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Location of `config.json` in the app config directory.
+pub fn path(app: &AppHandle) -> Result<PathBuf, ConfigError> {
+    app.path_resolver()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .ok_or_else(|| ConfigError::Io("Failed to resolve app config directory".to_string()))
+}
+
+/// Upgrades `value` to [`CURRENT_VERSION`] in place. Returns the version it
+/// started from.
+fn migrate(value: &mut serde_json::Value) -> Result<u64, ConfigError> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| ConfigError::Parse("Desktop config is not a JSON object".to_string()))?;
+    let found = match object.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| ConfigError::Parse("`version` is not a number".to_string()))?,
+    };
+    if found > u64::from(CURRENT_VERSION) {
+        return Err(ConfigError::UnsupportedVersion {
+            found,
+            supported: CURRENT_VERSION,
+        });
+    }
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(found as usize) {
+        migration(object);
+        object.insert("version".to_string(), (step as u64 + 1).into());
+    }
+    Ok(found)
+}
+
+/// Reads the desktop config, returning the defaults when none was saved yet.
+/// Files from older versions are migrated and written back, keeping the
+/// original next to it as `config.json.v<N>.bak`.
+pub fn load(app: &AppHandle) -> Result<DesktopConfig, ConfigError> {
+    let _guard = lock(app);
+    load_locked(app)
+}
+
+fn load_locked(app: &AppHandle) -> Result<DesktopConfig, ConfigError> {
+    let path = path(app)?;
+    if !path.exists() {
+        return Ok(DesktopConfig::default());
+    }
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| ConfigError::Io(format!("Failed to read config: {}", e)))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string()))?;
+    let from = migrate(&mut value)?;
+    let config: DesktopConfig =
+        serde_json::from_value(value).map_err(|e| ConfigError::Parse(e.to_string()))?;
+
+    if from < u64::from(CURRENT_VERSION) {
+        let backup = path.with_extension(format!("json.v{}.bak", from));
+        if let Err(e) = fs::copy(&path, &backup) {
+            eprintln!("Failed to back up desktop config before migrating: {}", e);
+        } else if let Err(e) = write_locked(app, &config) {
+            eprintln!("Failed to save migrated desktop config: {}", e);
+        } else {
+            println!(
+                "Migrated desktop config from version {} to {}",
+                from, CURRENT_VERSION
+            );
+        }
+    }
+    Ok(config)
+}
+
+/// Validates and saves `config`.
+pub fn save(app: &AppHandle, config: &DesktopConfig) -> Result<(), ConfigError> {
+    check(config)?;
+    let _guard = lock(app);
+    write_locked(app, config)
+}
+
+/// Loads the config, applies `change` and saves the result. No other read
+/// or write happens in between, so concurrent updates are not lost.
+pub fn update<F>(app: &AppHandle, change: F) -> Result<(), ConfigError>
+where
+    F: FnOnce(&mut DesktopConfig),
+{
+    let _guard = lock(app);
+    let mut config = load_locked(app)?;
+    change(&mut config);
+    check(&config)?;
+    write_locked(app, &config)
+}
+
+fn check(config: &DesktopConfig) -> Result<(), ConfigError> {
+    let errors = config.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(errors))
+    }
+}
+
+/// Writes `config`; the caller holds the [`PendingWrites`] lock.
+fn write_locked(app: &AppHandle, config: &DesktopConfig) -> Result<(), ConfigError> {
+    let path = path(app)?;
+    let contents = serde_json::to_string_pretty(config)
+        .map_err(|e| ConfigError::Io(format!("Failed to serialize config: {}", e)))?;
+    app.state::<OwnWrites>().record(&path, &contents);
+    write_atomic(&path, contents.as_bytes())
+        .map_err(|e| ConfigError::Io(format!("Failed to write config: {}", e)))
+}
+
+/// Writes a sibling `.tmp` file and renames it over `path`, so a crash never
+/// leaves a truncated file behind. Creates the parent directory, and keeps
+/// the permissions of the file being replaced.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    {
+        let mut file = File::create(&tmp)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+/// Blocks until any in-progress write has finished.
+pub fn flush(app: &AppHandle) {
+    drop(lock(app));
+}
+
+/// Whether a key names a credential, e.g. `password`, `token` or
+/// `OPENAI_API_KEY`.
+fn is_secret(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    [
+        "password",
+        "secret",
+        "token",
+        "authorization",
+        "credential",
+        "apikey",
+    ]
+    .iter()
+    .any(|word| key.contains(word))
+        || key == "key"
+        || key.ends_with("_key")
+}
+
+/// Copy of `value` with every string under a secret-looking key replaced,
+/// for logging.
+pub fn redact(value: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) if is_secret(key) && !s.is_empty() => {
+                            Value::String(REDACTED.to_string())
+                        }
+                        _ => redact(value),
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact).collect()),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn config(value: Value) -> DesktopConfig {
+        serde_json::from_value(value).expect("config deserializes")
+    }
+
+    fn invalid_fields(config: &DesktopConfig) -> Vec<String> {
+        config.validate().into_iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn migrates_unversioned_file() {
+        let mut value = json!({ "backend": { "port": 8080 }, "theme": "dark" });
+        assert_eq!(migrate(&mut value).unwrap(), 0);
+        assert_eq!(value["version"], json!(CURRENT_VERSION));
+        // Settings and unknown keys survive the migration.
+        let config = config(value);
+        assert_eq!(config.backend.port, Some(8080));
+        assert_eq!(config.other["theme"], json!("dark"));
+        // Missing sections get their defaults.
+        assert!(config.tray.keep_running_on_close);
+    }
+
+    #[test]
+    fn leaves_current_version_alone() {
+        let mut value = json!({ "version": CURRENT_VERSION });
+        assert_eq!(migrate(&mut value).unwrap(), u64::from(CURRENT_VERSION));
+        assert_eq!(value, json!({ "version": CURRENT_VERSION }));
+    }
+
+    #[test]
+    fn rejects_newer_and_malformed_versions() {
+        let mut value = json!({ "version": CURRENT_VERSION + 1 });
+        assert!(matches!(
+            migrate(&mut value),
+            Err(ConfigError::UnsupportedVersion { found, supported })
+                if found == u64::from(CURRENT_VERSION + 1) && supported == CURRENT_VERSION
+        ));
+        assert!(matches!(
+            migrate(&mut json!({ "version": "1" })),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            migrate(&mut json!([])),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(DesktopConfig::default().validate().is_empty());
+        assert!(config(json!({})).validate().is_empty());
+    }
+
+    #[test]
+    fn reports_invalid_backend_fields() {
+        let config = config(json!({
+            "version": CURRENT_VERSION,
+            "backend": {
+                "subcommand": " ",
+                "port": 0,
+                "ready_timeout_secs": 0,
+                "env": { "A=B": "x", "OK": "y" }
+            }
+        }));
+        assert_eq!(
+            invalid_fields(&config),
+            vec![
+                "backend.subcommand",
+                "backend.port",
+                "backend.ready_timeout_secs",
+                "backend.env.A=B"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_sections() {
+        let config = config(json!({
+            "version": 0,
+            "log_file": { "max_files": 0 },
+            "connection": { "active": "missing" },
+            "restart": { "initial_backoff_ms": 5000, "max_backoff_ms": 1000 },
+            "events": { "url": "http://localhost/ws" },
+            "notifications": {
+                "rules": [{}, { "quiet_hours": { "start": "25:00", "end": "07:00" } }]
+            },
+            "journal": { "max_entries": 0, "max_age_days": 0 },
+            "verification": { "poll_interval_secs": 10, "model_timeout_secs": 5 },
+            "workspaces": { "active": "missing" }
+        }));
+        assert_eq!(
+            invalid_fields(&config),
+            vec![
+                "version",
+                "log_file",
+                "connection",
+                "restart.max_backoff_ms",
+                "events.url",
+                "workspaces",
+                "notifications.rules[1]",
+                "journal.max_entries",
+                "journal.max_age_days",
+                "verification.model_timeout_secs"
+            ]
+        );
+    }
+
+    #[test]
+    fn redacts_secret_keys_at_any_depth() {
+        let value = json!({
+            "backend": { "env": { "OPENAI_API_KEY": "sk-1", "RUST_LOG": "debug" } },
+            "connection": {
+                "profiles": {
+                    "shared": { "auth": { "type": "basic", "username": "me", "password": "pw" } }
+                }
+            },
+            "tokens": [{ "token": "t" }],
+            "secret": "",
+            "key": 1
+        });
+        assert_eq!(
+            redact(&value),
+            json!({
+                "backend": { "env": { "OPENAI_API_KEY": REDACTED, "RUST_LOG": "debug" } },
+                "connection": {
+                    "profiles": {
+                        "shared": {
+                            "auth": { "type": "basic", "username": "me", "password": REDACTED }
+                        }
+                    }
+                },
+                "tokens": [{ "token": REDACTED }],
+                "secret": "",
+                "key": 1
+            })
+        );
+    }
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let mut config = DesktopConfig::default();
+        config
+            .backend
+            .env
+            .insert("ANTHROPIC_API_KEY".to_string(), "sk-ant-secret".to_string());
+        let debug = format!("{:?}", config);
+        assert!(debug.contains("ANTHROPIC_API_KEY"));
+        assert!(!debug.contains("sk-ant-secret"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn writes_atomically_keeping_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-config-{}-write-atomic",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("file.json");
+
+        write_atomic(&path, b"first").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!dir.join("nested").join("file.json.tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
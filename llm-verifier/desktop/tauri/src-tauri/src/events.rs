@@ -0,0 +1,585 @@
+//! Bridge from the backend's `/ws` event stream (`events/websocket_server.go`)
+//! to Tauri events. Each backend event is re-emitted as `events://<type>`;
+//! the state of the connection itself is reported as `events://connection`.
+//! `llm-verifier server` does not mount that endpoint yet; a backend that
+//! answers 404 is reported as `unsupported` instead of being retried.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::future::{self, Either};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::{Notify, RwLock};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::backend::profile::Profiles;
+use crate::backend::BackendProcess;
+use crate::config;
+use crate::journal::Journal;
+use crate::notifications;
+use crate::vault::Vault;
+
+/// First delay before reconnecting; doubled after every failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Idle time after which the bridge pings the server and checks whether the
+/// active profile now points elsewhere. Below the server's 60s read deadline.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// `EventType` in `events/event_manager.go`. Types added to the server after
+/// this list are kept as [`EventType::Other`] rather than dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventType {
+    VerificationStarted,
+    VerificationCompleted,
+    VerificationFailed,
+    ScoreChanged,
+    ModelAdded,
+    ModelRemoved,
+    ProviderAdded,
+    ProviderRemoved,
+    IssueDetected,
+    IssueResolved,
+    ConfigExported,
+    DatabaseMigration,
+    ClientConnected,
+    ClientDisconnected,
+    SystemHealthChanged,
+    MaintenanceMode,
+    BackupCompleted,
+    SecurityAlert,
+    Other(String),
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::VerificationStarted => "verification_started",
+            Self::VerificationCompleted => "verification_completed",
+            Self::VerificationFailed => "verification_failed",
+            Self::ScoreChanged => "score_changed",
+            Self::ModelAdded => "model_added",
+            Self::ModelRemoved => "model_removed",
+            Self::ProviderAdded => "provider_added",
+            Self::ProviderRemoved => "provider_removed",
+            Self::IssueDetected => "issue_detected",
+            Self::IssueResolved => "issue_resolved",
+            Self::ConfigExported => "config_exported",
+            Self::DatabaseMigration => "database_migration",
+            Self::ClientConnected => "client_connected",
+            Self::ClientDisconnected => "client_disconnected",
+            Self::SystemHealthChanged => "system_health_changed",
+            Self::MaintenanceMode => "maintenance_mode",
+            Self::BackupCompleted => "backup_completed",
+            Self::SecurityAlert => "security_alert",
+            Self::Other(name) => name,
+        }
+    }
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "verification_started" => Self::VerificationStarted,
+            "verification_completed" => Self::VerificationCompleted,
+            "verification_failed" => Self::VerificationFailed,
+            "score_changed" => Self::ScoreChanged,
+            "model_added" => Self::ModelAdded,
+            "model_removed" => Self::ModelRemoved,
+            "provider_added" => Self::ProviderAdded,
+            "provider_removed" => Self::ProviderRemoved,
+            "issue_detected" => Self::IssueDetected,
+            "issue_resolved" => Self::IssueResolved,
+            "config_exported" => Self::ConfigExported,
+            "database_migration" => Self::DatabaseMigration,
+            "client_connected" => Self::ClientConnected,
+            "client_disconnected" => Self::ClientDisconnected,
+            "system_health_changed" => Self::SystemHealthChanged,
+            "maintenance_mode" => Self::MaintenanceMode,
+            "backup_completed" => Self::BackupCompleted,
+            "security_alert" => Self::SecurityAlert,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+// Serialized as the bare type name, like the server does.
+impl Serialize for EventType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EventType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| Self::from_name(&name))
+    }
+}
+
+/// `Severity` in `events/event_manager.go`, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// `Event` in `events/event_manager.go`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+    #[serde(default)]
+    pub details: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default)]
+    pub model_id: Option<i64>,
+    #[serde(default)]
+    pub provider_id: Option<i64>,
+    #[serde(default)]
+    pub verification_id: Option<i64>,
+    #[serde(default)]
+    pub issue_id: Option<i64>,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<i64>,
+    #[serde(default)]
+    pub source: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Messages the server sends on `/ws`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Event {
+        event: Box<BackendEvent>,
+    },
+    /// Answer to a client message, such as the keep-alive ping.
+    Ack,
+    Error {
+        code: String,
+        message: String,
+    },
+}
+
+/// Event bridge settings, read from the `events` section of the desktop config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventSettings {
+    pub enabled: bool,
+    /// WebSocket URL of the event server. Defaults to `/ws` on the host of
+    /// the active connection profile, which `llm-verifier server` does not
+    /// serve yet.
+    pub url: Option<String>,
+    /// Event types to subscribe to; empty uses the server's default set.
+    pub types: Vec<EventType>,
+    /// Events below this severity are dropped.
+    pub min_severity: Severity,
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            url: None,
+            types: Vec::new(),
+            min_severity: Severity::Info,
+        }
+    }
+}
+
+impl EventSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(url) = &self.url {
+            let parsed =
+                reqwest::Url::parse(url).map_err(|e| format!("Invalid event URL: {}", e))?;
+            if parsed.scheme() != "ws" && parsed.scheme() != "wss" {
+                return Err("Event URL must be ws or wss".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.events)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeState {
+    Connecting,
+    Connected,
+    Disconnected,
+    Disabled,
+    /// The server answered 404: it has no event endpoint at this URL.
+    /// Retried only when the settings or the target URL change.
+    Unsupported,
+}
+
+/// Payload of `events://connection`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BridgeStatus {
+    pub state: BridgeState,
+    pub url: Option<String>,
+    pub error: Option<String>,
+    /// Delay before the next connection attempt while disconnected.
+    pub retry_in_ms: Option<u64>,
+}
+
+/// Managed state holding the bridge settings and its latest status.
+pub struct EventBridge {
+    settings: RwLock<EventSettings>,
+    status: RwLock<BridgeStatus>,
+    /// Woken when the settings change so the bridge reconnects with them.
+    reconnect: Notify,
+}
+
+impl EventBridge {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            settings: RwLock::new(EventSettings::load(app)),
+            status: RwLock::new(BridgeStatus {
+                state: BridgeState::Disconnected,
+                url: None,
+                error: None,
+                retry_in_ms: None,
+            }),
+            reconnect: Notify::new(),
+        }
+    }
+
+    /// Adopts changed settings and reconnects with them.
+    pub async fn reload(&self, settings: EventSettings) {
+        *self.settings.write().await = settings;
+        self.reconnect.notify_one();
+    }
+
+    async fn report(&self, app: &AppHandle, status: BridgeStatus) {
+        let _ = app.emit_all("events://connection", status.clone());
+        *self.status.write().await = status;
+    }
+}
+
+/// URL to connect to, with the subscription in its `types` query parameter.
+async fn resolve_url(app: &AppHandle, settings: &EventSettings) -> Result<String, String> {
+    let mut url = match &settings.url {
+        Some(url) => reqwest::Url::parse(url).map_err(|e| format!("Invalid event URL: {}", e))?,
+        None => {
+            let backend = app.state::<BackendProcess>();
+            let connection = app
+                .state::<Profiles>()
+                .connection(&backend, &app.state::<Vault>())
+                .await
+                .map_err(|e| e.to_string())?;
+            let mut url = reqwest::Url::parse(&connection.base_url).map_err(|e| e.to_string())?;
+            let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+            url.set_scheme(scheme)
+                .map_err(|_| format!("Cannot derive event URL from {}", connection.base_url))?;
+            url.set_path("/ws");
+            url
+        }
+    };
+    if !settings.types.is_empty() {
+        let types: Vec<&str> = settings.types.iter().map(|t| t.as_str()).collect();
+        url.query_pairs_mut().append_pair("types", &types.join(","));
+    }
+    Ok(url.into())
+}
+
+/// Starts the bridge for the lifetime of the app.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let bridge = app.state::<EventBridge>();
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let settings = bridge.settings.read().await.clone();
+            if !settings.enabled {
+                bridge
+                    .report(
+                        &app,
+                        BridgeStatus {
+                            state: BridgeState::Disabled,
+                            url: None,
+                            error: None,
+                            retry_in_ms: None,
+                        },
+                    )
+                    .await;
+                bridge.reconnect.notified().await;
+                continue;
+            }
+
+            let (url, error) = match resolve_url(&app, &settings).await {
+                Ok(url) => match run(&app, &bridge, &settings, &url, &mut backoff).await {
+                    Ok(()) => continue,
+                    Err(Disconnect::Unsupported(e)) => {
+                        println!("Backend serves no events at {}: {}", url, e);
+                        bridge
+                            .report(
+                                &app,
+                                BridgeStatus {
+                                    state: BridgeState::Unsupported,
+                                    url: Some(url.clone()),
+                                    error: Some(e),
+                                    retry_in_ms: None,
+                                },
+                            )
+                            .await;
+                        wait_for_change(&app, &bridge, &settings, &url).await;
+                        backoff = INITIAL_BACKOFF;
+                        continue;
+                    }
+                    Err(Disconnect::Failed(e)) => (Some(url), e),
+                },
+                Err(e) => (None, e),
+            };
+            bridge
+                .report(
+                    &app,
+                    BridgeStatus {
+                        state: BridgeState::Disconnected,
+                        url,
+                        error: Some(error),
+                        retry_in_ms: Some(backoff.as_millis() as u64),
+                    },
+                )
+                .await;
+
+            // Settings changes cut the wait short.
+            let _ = tokio::time::timeout(backoff, bridge.reconnect.notified()).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+}
+
+/// Why a connection ended.
+enum Disconnect {
+    /// The server has no event endpoint; retrying will not help.
+    Unsupported(String),
+    Failed(String),
+}
+
+impl From<String> for Disconnect {
+    fn from(error: String) -> Self {
+        Self::Failed(error)
+    }
+}
+
+/// Waits until the settings change or the active profile points elsewhere.
+async fn wait_for_change(
+    app: &AppHandle,
+    bridge: &EventBridge,
+    settings: &EventSettings,
+    url: &str,
+) {
+    loop {
+        if tokio::time::timeout(MAX_BACKOFF, bridge.reconnect.notified())
+            .await
+            .is_ok()
+        {
+            return;
+        }
+        if resolve_url(app, settings).await.as_deref() != Ok(url) {
+            return;
+        }
+    }
+}
+
+fn is_not_found(error: &tokio_tungstenite::tungstenite::Error) -> bool {
+    matches!(
+        error,
+        tokio_tungstenite::tungstenite::Error::Http(response) if response.status().as_u16() == 404
+    )
+}
+
+/// Runs one connection until it fails or should be re-established. Returns
+/// `Ok` when the settings or the target URL changed, to reconnect right away.
+async fn run(
+    app: &AppHandle,
+    bridge: &EventBridge,
+    settings: &EventSettings,
+    url: &str,
+    backoff: &mut Duration,
+) -> Result<(), Disconnect> {
+    bridge
+        .report(
+            app,
+            BridgeStatus {
+                state: BridgeState::Connecting,
+                url: Some(url.to_string()),
+                error: None,
+                retry_in_ms: None,
+            },
+        )
+        .await;
+    let (mut socket, _) = match tokio_tungstenite::connect_async(url).await {
+        Ok(connected) => connected,
+        Err(e) if is_not_found(&e) => return Err(Disconnect::Unsupported(e.to_string())),
+        Err(e) => return Err(Disconnect::Failed(e.to_string())),
+    };
+    *backoff = INITIAL_BACKOFF;
+    println!("Connected to backend events at {}", url);
+    bridge
+        .report(
+            app,
+            BridgeStatus {
+                state: BridgeState::Connected,
+                url: Some(url.to_string()),
+                error: None,
+                retry_in_ms: None,
+            },
+        )
+        .await;
+
+    loop {
+        let next = {
+            let next = tokio::time::timeout(IDLE_CHECK_INTERVAL, socket.next());
+            let notified = bridge.reconnect.notified();
+            futures_util::pin_mut!(next, notified);
+            match future::select(next, notified).await {
+                Either::Left((next, _)) => next,
+                // The settings changed.
+                Either::Right(_) => {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+            }
+        };
+
+        let message = match next {
+            Err(_) => {
+                if resolve_url(app, settings).await.as_deref() != Ok(url) {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+                socket
+                    .send(Message::Text(r#"{"type":"ping"}"#.to_string()))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                continue;
+            }
+            Ok(None) => return Err("Connection closed by the server".to_string().into()),
+            Ok(Some(message)) => message.map_err(|e| e.to_string())?,
+        };
+
+        match message {
+            Message::Text(text) => forward(app, settings, &text),
+            Message::Close(frame) => {
+                return Err(Disconnect::Failed(match frame {
+                    Some(frame) => format!("Connection closed by the server: {}", frame.reason),
+                    None => "Connection closed by the server".to_string(),
+                }))
+            }
+            // tungstenite answers pings itself.
+            _ => {}
+        }
+    }
+}
+
+fn forward(app: &AppHandle, settings: &EventSettings, text: &str) {
+    match serde_json::from_str::<ServerMessage>(text) {
+        Ok(ServerMessage::Event { event }) => {
+            // The journal and notification rules see every event; the
+            // bridge's severity threshold only applies to the UI.
+            app.state::<Journal>().append(&event);
+            notifications::notify(app, &event);
+            if event.severity >= settings.min_severity {
+                let name = format!("events://{}", event.event_type.as_str());
+                let _ = app.emit_all(&name, event);
+            }
+        }
+        Ok(ServerMessage::Ack) => {}
+        Ok(ServerMessage::Error { code, message }) => {
+            eprintln!("Backend event server error ({}): {}", code, message)
+        }
+        Err(e) => eprintln!("Ignoring unreadable backend event: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_event_bridge_status(
+    bridge: State<'_, EventBridge>,
+) -> Result<BridgeStatus, String> {
+    Ok(bridge.status.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn get_event_settings(bridge: State<'_, EventBridge>) -> Result<EventSettings, String> {
+    Ok(bridge.settings.read().await.clone())
+}
+
+/// Saves the bridge settings and reconnects with them.
+#[tauri::command]
+pub async fn set_event_settings(
+    app: AppHandle,
+    bridge: State<'_, EventBridge>,
+    settings: EventSettings,
+) -> Result<EventSettings, String> {
+    settings.validate()?;
+
+    config::update(&app, |config| config.events = settings.clone()).map_err(|e| e.to_string())?;
+
+    bridge.reload(settings.clone()).await;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::MockServer;
+
+    #[test]
+    fn keeps_unknown_event_types() {
+        let text = r#"{"type":"event","event":{"id":"1","type":"quota_exceeded","severity":"warning","title":"Quota","message":"","timestamp":"2024-01-02T10:00:00Z"}}"#;
+        let event = match serde_json::from_str::<ServerMessage>(text).unwrap() {
+            ServerMessage::Event { event } => event,
+            other => panic!("unexpected message: {:?}", other),
+        };
+        assert_eq!(
+            event.event_type,
+            EventType::Other("quota_exceeded".to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(&event.event_type).unwrap(),
+            "quota_exceeded"
+        );
+
+        let known: EventType = serde_json::from_str(r#""score_changed""#).unwrap();
+        assert_eq!(known, EventType::ScoreChanged);
+        assert_eq!(serde_json::to_value(&known).unwrap(), "score_changed");
+    }
+
+    #[tokio::test]
+    async fn recognises_a_server_without_the_event_endpoint() {
+        // Unmatched requests get a 404, like `llm-verifier server` for `/ws`.
+        let server = MockServer::start().await;
+        let url = server.uri().replacen("http", "ws", 1) + "/ws";
+        let error = tokio_tungstenite::connect_async(url).await.unwrap_err();
+        assert!(is_not_found(&error));
+    }
+}
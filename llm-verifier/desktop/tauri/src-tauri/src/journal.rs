@@ -0,0 +1,731 @@
+//! Append-only journal of the events received from the backend, kept in
+//! `events.jsonl` in the app data dir so they can be searched, exported and
+//! replayed after the fact.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::config;
+use crate::events::{BackendEvent, EventType, Severity};
+
+const JOURNAL_FILE_NAME: &str = "events.jsonl";
+
+/// Default and maximum page size of `query_events`.
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// Longest pause between two replayed events, however far apart they were.
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(5);
+
+/// Range `replay_events` clamps its `speed` to.
+const MIN_REPLAY_SPEED: f64 = 0.01;
+const MAX_REPLAY_SPEED: f64 = 1000.0;
+
+/// Journal settings, read from the `journal` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalOptions {
+    pub max_entries: usize,
+    pub max_age_days: i64,
+}
+
+impl Default for JournalOptions {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_age_days: 30,
+        }
+    }
+}
+
+impl JournalOptions {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.journal)
+            .unwrap_or_default()
+    }
+}
+
+/// One line of `events.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Increases with every entry; used as the `query_events` cursor.
+    pub seq: u64,
+    pub received_at: DateTime<Utc>,
+    pub event: BackendEvent,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EventFilter {
+    /// Empty matches every type.
+    pub types: Vec<EventType>,
+    pub min_severity: Option<Severity>,
+    pub model_id: Option<i64>,
+    pub provider_id: Option<i64>,
+    /// Case-insensitive substring of the title or message.
+    pub text: Option<String>,
+}
+
+/// Time range on the event timestamp; either end may be open.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TimeRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &BackendEvent) -> bool {
+        (self.types.is_empty() || self.types.contains(&event.event_type))
+            && self.min_severity.map_or(true, |min| event.severity >= min)
+            && self.model_id.map_or(true, |id| event.model_id == Some(id))
+            && self
+                .provider_id
+                .map_or(true, |id| event.provider_id == Some(id))
+            && self.text.as_ref().map_or(true, |text| {
+                let text = text.to_lowercase();
+                event.title.to_lowercase().contains(&text)
+                    || event.message.to_lowercase().contains(&text)
+            })
+    }
+}
+
+impl TimeRange {
+    fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.from.map_or(true, |from| at >= from) && self.to.map_or(true, |to| at < to)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPage {
+    /// Newest first.
+    pub entries: Vec<JournalEntry>,
+    /// Pass as `cursor` to fetch the next, older page.
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Json,
+    Jsonl,
+    Csv,
+}
+
+/// A change to `events.jsonl`, carried out by the writer thread.
+enum FileOp {
+    Append(String),
+    Rewrite(String),
+    /// Answered once every earlier change is on disk.
+    Flush(mpsc::Sender<()>),
+}
+
+/// Starts the thread that owns `events.jsonl`. Changes are queued while the
+/// store is locked, so the file sees them in the order the entries do, and
+/// the event task never waits on the disk.
+fn spawn_writer(path: PathBuf) -> Option<mpsc::Sender<FileOp>> {
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("event-journal".to_string())
+        .spawn(move || {
+            for op in receiver {
+                let result = match op {
+                    FileOp::Append(line) => append_line(&path, &line),
+                    FileOp::Rewrite(contents) => config::write_atomic(&path, contents.as_bytes()),
+                    FileOp::Flush(done) => {
+                        let _ = done.send(());
+                        Ok(())
+                    }
+                };
+                if let Err(e) = result {
+                    eprintln!("Failed to write event journal: {}", e);
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => Some(sender),
+        Err(e) => {
+            eprintln!("Event journal will not be saved: {}", e);
+            None
+        }
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+struct Store {
+    writer: Option<mpsc::Sender<FileOp>>,
+    options: JournalOptions,
+    entries: Vec<JournalEntry>,
+    next_seq: u64,
+}
+
+/// Managed state holding the journal; entries are kept in memory as well.
+pub struct Journal {
+    store: Mutex<Store>,
+    /// Bumped to cancel a running replay.
+    replay_generation: AtomicU64,
+}
+
+impl Journal {
+    /// Loads the entries written by earlier runs, dropping any that are past
+    /// retention.
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(JOURNAL_FILE_NAME));
+        Self::open(path, JournalOptions::load(app))
+    }
+
+    fn open(path: Option<PathBuf>, options: JournalOptions) -> Self {
+        let entries = path.as_deref().map(read_entries).unwrap_or_default();
+        let next_seq = entries.last().map_or(1, |entry| entry.seq + 1);
+        let journal = Self {
+            store: Mutex::new(Store {
+                writer: path.and_then(spawn_writer),
+                options,
+                entries,
+                next_seq,
+            }),
+            replay_generation: AtomicU64::new(0),
+        };
+        journal.enforce_retention(&mut journal.lock(), true);
+        journal
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records `event`; the file is appended to in the background.
+    pub fn append(&self, event: &BackendEvent) {
+        let mut store = self.lock();
+        let entry = JournalEntry {
+            seq: store.next_seq,
+            received_at: Utc::now(),
+            event: event.clone(),
+        };
+        store.next_seq += 1;
+
+        if let Some(writer) = &store.writer {
+            match serde_json::to_string(&entry) {
+                Ok(line) => {
+                    let _ = writer.send(FileOp::Append(line));
+                }
+                Err(e) => eprintln!("Failed to append to event journal: {}", e),
+            }
+        }
+        store.entries.push(entry);
+        self.enforce_retention(&mut store, false);
+    }
+
+    /// Blocks until every queued change has reached the file.
+    pub fn flush(&self) {
+        let (done, finished) = mpsc::channel();
+        let queued = self
+            .lock()
+            .writer
+            .as_ref()
+            .map_or(false, |writer| writer.send(FileOp::Flush(done)).is_ok());
+        if queued {
+            let _ = finished.recv();
+        }
+    }
+
+    /// Adopts changed retention limits, dropping what they no longer keep.
+    pub fn reload(&self, options: JournalOptions) {
+        let mut store = self.lock();
+        store.options = options;
+        self.enforce_retention(&mut store, true);
+    }
+
+    /// Drops entries past the age or count limit. The file is only rewritten
+    /// once it is a tenth over the count limit, or on `force`, so appends stay
+    /// cheap.
+    fn enforce_retention(&self, store: &mut Store, force: bool) {
+        let cutoff = Utc::now() - chrono::Duration::days(store.options.max_age_days.max(0));
+        let expired = store
+            .entries
+            .iter()
+            .take_while(|entry| entry.received_at < cutoff)
+            .count();
+        let over = store
+            .entries
+            .len()
+            .saturating_sub(expired)
+            .saturating_sub(store.options.max_entries);
+        let slack = store.options.max_entries / 10;
+        if expired == 0 && over == 0 {
+            return;
+        }
+        if !force && expired == 0 && over <= slack {
+            return;
+        }
+
+        store.entries.drain(..expired + over);
+        if let Some(writer) = &store.writer {
+            match to_jsonl(&store.entries) {
+                Ok(contents) => {
+                    let _ = writer.send(FileOp::Rewrite(contents));
+                }
+                Err(e) => eprintln!("Failed to compact event journal: {}", e),
+            }
+        }
+    }
+
+    /// Up to `limit` (clamped to 1..=1000) matching entries older than
+    /// `cursor`, newest first.
+    fn page(
+        &self,
+        filter: &EventFilter,
+        range: &TimeRange,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> EventPage {
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let mut entries: Vec<JournalEntry> = self
+            .select(filter, range)
+            .into_iter()
+            .rev()
+            .filter(|entry| cursor.map_or(true, |cursor| entry.seq < cursor))
+            .take(limit + 1)
+            .collect();
+        let next_cursor = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|entry| entry.seq)
+        } else {
+            None
+        };
+        EventPage {
+            entries,
+            next_cursor,
+        }
+    }
+
+    fn select(&self, filter: &EventFilter, range: &TimeRange) -> Vec<JournalEntry> {
+        self.lock()
+            .entries
+            .iter()
+            .filter(|entry| range.contains(entry.event.timestamp) && filter.matches(&entry.event))
+            .cloned()
+            .collect()
+    }
+}
+
+fn read_entries(path: &Path) -> Vec<JournalEntry> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return Vec::new(),
+    };
+    let mut skipped = 0;
+    let entries = BufReader::new(file)
+        .lines()
+        .filter_map(|line| {
+            // A crash mid-append can leave a truncated last line.
+            let entry = line.ok().and_then(|line| serde_json::from_str(&line).ok());
+            if entry.is_none() {
+                skipped += 1;
+            }
+            entry
+        })
+        .collect();
+    if skipped > 0 {
+        eprintln!("Skipped {} unreadable event journal lines", skipped);
+    }
+    entries
+}
+
+/// One JSON object per line, as in `events.jsonl`.
+fn to_jsonl(entries: &[JournalEntry]) -> serde_json::Result<String> {
+    entries
+        .iter()
+        .map(|entry| serde_json::to_string(entry).map(|line| line + "\n"))
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(entries: &[JournalEntry]) -> String {
+    let mut out = String::from(
+        "seq,received_at,timestamp,type,severity,title,message,model_id,provider_id,verification_id,source\n",
+    );
+    let id = |id: Option<i64>| id.map(|id| id.to_string()).unwrap_or_default();
+    for entry in entries {
+        let event = &entry.event;
+        let fields = [
+            entry.seq.to_string(),
+            entry.received_at.to_rfc3339(),
+            event.timestamp.to_rfc3339(),
+            event.event_type.as_str().to_string(),
+            event.severity.as_str().to_string(),
+            csv_field(&event.title),
+            csv_field(&event.message),
+            id(event.model_id),
+            id(event.provider_id),
+            id(event.verification_id),
+            csv_field(&event.source),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Journal entries matching `filter` and `range`, newest first, one page at
+/// a time. `cursor` is the `next_cursor` of the previous page.
+#[tauri::command]
+pub async fn query_events(
+    journal: State<'_, Journal>,
+    filter: Option<EventFilter>,
+    range: Option<TimeRange>,
+    cursor: Option<u64>,
+    limit: Option<usize>,
+) -> Result<EventPage, String> {
+    Ok(journal.page(
+        &filter.unwrap_or_default(),
+        &range.unwrap_or_default(),
+        cursor,
+        limit.unwrap_or(DEFAULT_PAGE_SIZE),
+    ))
+}
+
+/// Matching journal entries, oldest first, as JSON, JSON Lines or CSV.
+#[tauri::command]
+pub async fn export_events(
+    journal: State<'_, Journal>,
+    format: ExportFormat,
+    filter: Option<EventFilter>,
+    range: Option<TimeRange>,
+) -> Result<String, String> {
+    let entries = journal.select(&filter.unwrap_or_default(), &range.unwrap_or_default());
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&entries).map_err(|e| e.to_string()),
+        ExportFormat::Jsonl => to_jsonl(&entries).map_err(|e| e.to_string()),
+        ExportFormat::Csv => Ok(to_csv(&entries)),
+    }
+}
+
+/// Payload of `events://replay_finished`.
+#[derive(Debug, Clone, Serialize)]
+struct ReplayFinished {
+    replayed: usize,
+    cancelled: bool,
+}
+
+/// Pause before replaying an event that originally came `elapsed` after the
+/// previous one.
+fn replay_gap(elapsed: chrono::Duration, speed: f64) -> Duration {
+    elapsed
+        .to_std()
+        .unwrap_or_default()
+        .div_f64(speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED))
+        .min(MAX_REPLAY_GAP)
+}
+
+/// Re-emits matching journal entries, oldest first, as `events://replay`.
+/// With a `speed` the original spacing is kept, divided by `speed` (clamped
+/// to 0.01–1000) and capped; without one they are sent back to back.
+/// Starting a replay cancels the one in progress.
+#[tauri::command]
+pub async fn replay_events(
+    app: AppHandle,
+    journal: State<'_, Journal>,
+    filter: Option<EventFilter>,
+    range: Option<TimeRange>,
+    speed: Option<f64>,
+) -> Result<usize, String> {
+    if let Some(speed) = speed {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(format!(
+                "Replay speed must be a positive number, got {}",
+                speed
+            ));
+        }
+    }
+    let entries = journal.select(&filter.unwrap_or_default(), &range.unwrap_or_default());
+    let count = entries.len();
+    let generation = journal.replay_generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    tauri::async_runtime::spawn(async move {
+        let journal = app.state::<Journal>();
+        let mut previous: Option<DateTime<Utc>> = None;
+        let mut replayed = 0;
+        let mut cancelled = false;
+        for entry in entries {
+            if journal.replay_generation.load(Ordering::SeqCst) != generation {
+                cancelled = true;
+                break;
+            }
+            if let (Some(speed), Some(previous)) = (speed, previous) {
+                tokio::time::sleep(replay_gap(entry.event.timestamp - previous, speed)).await;
+            }
+            previous = Some(entry.event.timestamp);
+            let _ = app.emit_all("events://replay", entry);
+            replayed += 1;
+        }
+        let _ = app.emit_all(
+            "events://replay_finished",
+            ReplayFinished {
+                replayed,
+                cancelled,
+            },
+        );
+    });
+    Ok(count)
+}
+
+#[tauri::command]
+pub async fn stop_replay(journal: State<'_, Journal>) -> Result<(), String> {
+    journal.replay_generation.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-journal-{}-{}",
+            std::process::id(),
+            test
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(JOURNAL_FILE_NAME)
+    }
+
+    fn event(event_type: EventType, severity: Severity, title: &str) -> BackendEvent {
+        BackendEvent {
+            id: title.to_string(),
+            event_type,
+            severity,
+            title: title.to_string(),
+            message: String::new(),
+            details: None,
+            model_id: None,
+            provider_id: None,
+            verification_id: None,
+            issue_id: None,
+            client_id: None,
+            user_id: None,
+            source: "test".to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn seqs(page: &EventPage) -> Vec<u64> {
+        page.entries.iter().map(|entry| entry.seq).collect()
+    }
+
+    fn file_seqs(path: &Path) -> Vec<u64> {
+        read_entries(path).iter().map(|entry| entry.seq).collect()
+    }
+
+    #[test]
+    fn pages_newest_first_by_cursor() {
+        let journal = Journal::open(None, JournalOptions::default());
+        for i in 0..5 {
+            journal.append(&event(
+                EventType::ModelAdded,
+                Severity::Info,
+                &format!("model {}", i),
+            ));
+        }
+        let all = (EventFilter::default(), TimeRange::default());
+
+        let first = journal.page(&all.0, &all.1, None, 2);
+        assert_eq!(seqs(&first), vec![5, 4]);
+        assert_eq!(first.next_cursor, Some(4));
+        let second = journal.page(&all.0, &all.1, first.next_cursor, 2);
+        assert_eq!(seqs(&second), vec![3, 2]);
+        let last = journal.page(&all.0, &all.1, second.next_cursor, 2);
+        assert_eq!(seqs(&last), vec![1]);
+        assert_eq!(last.next_cursor, None);
+
+        // The limit is clamped rather than rejected.
+        assert_eq!(seqs(&journal.page(&all.0, &all.1, None, 0)), vec![5]);
+    }
+
+    #[test]
+    fn filters_by_type_severity_ids_text_and_time() {
+        let journal = Journal::open(None, JournalOptions::default());
+        let started = Utc::now();
+        journal.append(&event(
+            EventType::VerificationFailed,
+            Severity::Error,
+            "GPT-4 failed",
+        ));
+        journal.append(&BackendEvent {
+            model_id: Some(7),
+            provider_id: Some(2),
+            ..event(EventType::ScoreChanged, Severity::Info, "Score up")
+        });
+        journal.append(&BackendEvent {
+            timestamp: started - chrono::Duration::hours(1),
+            ..event(EventType::SecurityAlert, Severity::Critical, "Old alert")
+        });
+        let page = |filter: EventFilter, range: TimeRange| {
+            seqs(&journal.page(&filter, &range, None, DEFAULT_PAGE_SIZE))
+        };
+
+        let by_type = EventFilter {
+            types: vec![EventType::ScoreChanged, EventType::SecurityAlert],
+            ..EventFilter::default()
+        };
+        assert_eq!(page(by_type, TimeRange::default()), vec![3, 2]);
+        let severe = EventFilter {
+            min_severity: Some(Severity::Error),
+            ..EventFilter::default()
+        };
+        assert_eq!(page(severe, TimeRange::default()), vec![3, 1]);
+        let by_ids = EventFilter {
+            model_id: Some(7),
+            provider_id: Some(2),
+            ..EventFilter::default()
+        };
+        assert_eq!(page(by_ids, TimeRange::default()), vec![2]);
+        let by_text = EventFilter {
+            text: Some("gpt-4".to_string()),
+            ..EventFilter::default()
+        };
+        assert_eq!(page(by_text, TimeRange::default()), vec![1]);
+        let recent = TimeRange {
+            from: Some(started - chrono::Duration::minutes(1)),
+            to: None,
+        };
+        assert_eq!(page(EventFilter::default(), recent), vec![2, 1]);
+        let older = TimeRange {
+            from: None,
+            to: Some(started - chrono::Duration::minutes(1)),
+        };
+        assert_eq!(page(EventFilter::default(), older), vec![3]);
+    }
+
+    #[test]
+    fn compacts_the_file_past_the_count_limit() {
+        let path = scratch_path("count");
+        let options = JournalOptions {
+            max_entries: 10,
+            max_age_days: 30,
+        };
+        let journal = Journal::open(Some(path.clone()), options.clone());
+        for i in 0..11 {
+            journal.append(&event(
+                EventType::ModelAdded,
+                Severity::Info,
+                &i.to_string(),
+            ));
+        }
+        // Within a tenth over the limit, appends do not rewrite the file.
+        journal.flush();
+        assert_eq!(file_seqs(&path), (1..=11).collect::<Vec<_>>());
+
+        journal.append(&event(EventType::ModelAdded, Severity::Info, "11"));
+        journal.flush();
+        assert_eq!(file_seqs(&path), (3..=12).collect::<Vec<_>>());
+        assert!(!path.with_extension("jsonl.tmp").exists());
+
+        // Numbering continues from the file after a restart.
+        let reopened = Journal::open(Some(path.clone()), options);
+        reopened.append(&event(EventType::ModelAdded, Severity::Info, "12"));
+        reopened.flush();
+        assert_eq!(file_seqs(&path).last(), Some(&13));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn drops_expired_entries_on_open_and_reload() {
+        let path = scratch_path("age");
+        let old = JournalEntry {
+            seq: 1,
+            received_at: Utc::now() - chrono::Duration::days(40),
+            event: event(EventType::ModelAdded, Severity::Info, "old"),
+        };
+        let recent = JournalEntry {
+            seq: 2,
+            received_at: Utc::now() - chrono::Duration::days(5),
+            event: event(EventType::ModelAdded, Severity::Info, "recent"),
+        };
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, to_jsonl(&[old, recent]).unwrap()).unwrap();
+
+        let journal = Journal::open(Some(path.clone()), JournalOptions::default());
+        journal.flush();
+        assert_eq!(file_seqs(&path), vec![2]);
+
+        journal.reload(JournalOptions {
+            max_entries: 10,
+            max_age_days: 1,
+        });
+        journal.flush();
+        assert!(file_seqs(&path).is_empty());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn escapes_csv_fields() {
+        let entry = JournalEntry {
+            seq: 1,
+            received_at: Utc::now(),
+            event: BackendEvent {
+                message: "line one\nline two".to_string(),
+                source: "plain".to_string(),
+                ..event(
+                    EventType::IssueDetected,
+                    Severity::Warning,
+                    "Say \"hi\", twice",
+                )
+            },
+        };
+        let csv = to_csv(&[entry]);
+        let (_, row) = csv.split_once('\n').unwrap();
+        assert!(row.contains(
+            ",issue_detected,warning,\"Say \"\"hi\"\", twice\",\"line one\nline two\",,,,plain\n"
+        ));
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("cr\r"), "\"cr\r\"");
+        assert_eq!(csv_field("plain"), "plain");
+    }
+
+    #[test]
+    fn replay_gap_scales_and_caps() {
+        let second = chrono::Duration::seconds(1);
+        assert_eq!(replay_gap(second, 2.0), Duration::from_millis(500));
+        assert_eq!(replay_gap(chrono::Duration::hours(1), 1.0), MAX_REPLAY_GAP);
+        assert_eq!(replay_gap(-second, 1.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn replay_gap_clamps_extreme_speeds() {
+        let second = chrono::Duration::seconds(1);
+        // Would overflow `Duration` without the clamp.
+        assert_eq!(
+            replay_gap(chrono::Duration::days(365), 1e-20),
+            MAX_REPLAY_GAP
+        );
+        assert_eq!(replay_gap(second, 1e20), Duration::from_millis(1));
+    }
+}
@@ -1,64 +1,157 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::process::{Command, Stdio};
-use std::sync::Mutex;
+mod api;
+mod backend;
+mod cache;
+mod config;
+mod events;
+mod journal;
+mod notifications;
+mod session;
+mod shutdown;
+mod system;
+mod tray;
+mod vault;
+mod verification;
+mod watcher;
+mod workspace;
+
+use std::time::Duration;
+
+use api::Api;
+use backend::binary::{self, Resolution};
+use backend::config_file;
+use backend::health;
+use backend::launch::{self, LaunchOptions};
+use backend::lockfile::{self, Orphan};
+use backend::logs::{self, BackendLogs};
+use backend::port::{self, Endpoint};
+use backend::profile::{self, ConnectionProfile, Profiles};
+use backend::watchdog::{self, Watchdog};
+use backend::{BackendError, BackendProcess, BackendStatus};
+use cache::ResponseCache;
+use config::{ConfigError, DesktopConfig, FieldError};
+use events::EventBridge;
+use journal::Journal;
+use notifications::Notifier;
+use session::Session;
+use shutdown::Shutdown;
 use tauri::{AppHandle, Manager, State};
-use tokio::process::Command as TokioCommand;
+use vault::Vault;
+use verification::Verifications;
+use workspace::Workspaces;
 
-#[derive(Default)]
-struct BackendProcess(Mutex<Option<std::process::Child>>);
+/// Start/stop only make sense for the backend the app spawns itself.
+async fn require_managed(profiles: &Profiles) -> Result<(), BackendError> {
+    match profiles.active().await {
+        ConnectionProfile::Managed => Ok(()),
+        ConnectionProfile::Remote { base_url, .. } => Err(BackendError::RemoteProfile(base_url)),
+    }
+}
 
 #[tauri::command]
-async fn start_backend(app: AppHandle) -> Result<String, String> {
-    // Get the backend executable path
-    let backend_path = app
-        .path_resolver()
-        .resolve_resource("../llm-verifier")
-        .ok_or("Failed to resolve backend path")?;
-
-    println!("Starting backend: {:?}", backend_path);
-
-    // Spawn the backend process
-    let child = TokioCommand::new(&backend_path)
-        .arg("api")
-        .arg("--port")
-        .arg("8080")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to start backend: {}", e))?;
-
-    // Store the child process (simplified - in production you'd want better process management)
-
-    Ok("Backend started successfully".to_string())
+async fn start_backend(app: AppHandle) -> Result<String, BackendError> {
+    launch_managed(&app).await
 }
 
+/// Starts the managed backend for the active workspace and waits until it is
+/// healthy. Backs `start_backend` and every other place that starts it.
+pub async fn launch_managed(app: &AppHandle) -> Result<String, BackendError> {
+    let backend = app.state::<BackendProcess>();
+    let logs = app.state::<BackendLogs>();
+    require_managed(&app.state::<Profiles>()).await?;
+    let options = LaunchOptions::load(app)?;
+
+    let binary = binary::resolve(options.binary_path.as_deref())?;
+    for rejected in &binary.rejected {
+        println!(
+            "Skipped backend candidate ({:?}) {:?}: {}",
+            rejected.source, rejected.path, rejected.reason
+        );
+    }
+    let backend_path = binary.path;
+
+    println!("Starting backend ({:?}): {:?}", binary.source, backend_path);
+
+    // A locked vault fails the start rather than launching without keys.
+    let workspace = app.state::<Workspaces>().active().await;
+    let secrets = app
+        .state::<Vault>()
+        .backend_env(|name| workspace.has_secret(name) && !profile::is_credential(name))
+        .map_err(BackendError::Vault)?;
+
+    launch::probe(&backend_path, &options.subcommand).await?;
+    let endpoint = Endpoint::local(port::allocate(options.port)?);
+    let mut spec = options.to_spec(&backend_path, endpoint.clone());
+    spec.env.extend(secrets);
+    let log_seq = logs.next_seq();
+    let pid = backend.start(spec).await?;
+
+    let timeout = Duration::from_secs(options.ready_timeout_secs);
+    if let Err(e) = health::wait_ready(&backend, &logs, &endpoint, log_seq, timeout).await {
+        // Don't leave a half-started backend behind; it may have exited already.
+        let _ = backend.stop().await;
+        return Err(e);
+    }
+
+    Ok(format!(
+        "Backend started successfully (pid {}, {}, {})",
+        pid,
+        endpoint.base_url(),
+        backend_path.display()
+    ))
+}
+
+#[tauri::command]
+async fn stop_backend(
+    backend: State<'_, BackendProcess>,
+    profiles: State<'_, Profiles>,
+) -> Result<String, BackendError> {
+    require_managed(&profiles).await?;
+    let exit = backend.stop().await?;
+    match exit.code {
+        Some(code) => Ok(format!("Backend stopped successfully (exit code {})", code)),
+        None => Ok("Backend stopped successfully".to_string()),
+    }
+}
+
+/// Stops the managed backend, if running, and starts it again so it picks up
+/// config changes.
 #[tauri::command]
-async fn stop_backend() -> Result<String, String> {
-    // In a real implementation, you'd track and kill the backend process
-    Ok("Backend stopped successfully".to_string())
+async fn restart_backend(
+    app: AppHandle,
+    backend: State<'_, BackendProcess>,
+    profiles: State<'_, Profiles>,
+) -> Result<String, BackendError> {
+    require_managed(&profiles).await?;
+    match backend.stop().await {
+        Ok(_) | Err(BackendError::NotRunning) => {}
+        Err(e) => return Err(e),
+    }
+    launch_managed(&app).await
 }
 
 #[tauri::command]
-async fn get_backend_status() -> Result<serde_json::Value, String> {
-    // Simplified status check
-    Ok(serde_json::json!({
-        "running": false,
-        "port": "8080",
-        "host": "localhost"
-    }))
+async fn get_backend_status(
+    backend: State<'_, BackendProcess>,
+    profiles: State<'_, Profiles>,
+) -> Result<BackendStatus, String> {
+    match profiles.active().await {
+        ConnectionProfile::Managed => Ok(backend.status().await),
+        ConnectionProfile::Remote { base_url, .. } => Ok(BackendStatus::remote(
+            base_url,
+            profiles.remote_health().await,
+        )),
+    }
 }
 
+/// Reports which backend binary `start_backend` would launch, and why other
+/// locations were skipped.
 #[tauri::command]
-async fn get_system_info() -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({
-        "platform": std::env::consts::OS,
-        "arch": std::env::consts::ARCH,
-        "version": env!("CARGO_PKG_VERSION"),
-        "rustc": "1.70.0", // Would be dynamic in real implementation
-        "tauri": "1.5.0"
-    }))
+async fn resolve_backend_binary(app: AppHandle) -> Result<Resolution, BackendError> {
+    let options = LaunchOptions::load(&app)?;
+    binary::resolve(options.binary_path.as_deref())
 }
 
 #[tauri::command]
@@ -81,35 +174,157 @@ async fn save_file() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-async fn load_config() -> Result<serde_json::Value, String> {
-    // Load configuration from Tauri's app data directory
-    Ok(serde_json::json!({}))
+async fn load_config(app: AppHandle) -> Result<DesktopConfig, ConfigError> {
+    config::load(&app)
 }
 
+/// Saves the whole desktop config, then hands each section to the managed
+/// state that keeps it in memory. The watcher skips the app's own writes, so
+/// nothing else would reload them. New remote credentials go to the vault.
 #[tauri::command]
-async fn save_config(config: serde_json::Value) -> Result<String, String> {
-    // Save configuration to Tauri's app data directory
-    println!("Saving config: {:?}", config);
+async fn save_config(app: AppHandle, mut config: DesktopConfig) -> Result<String, ConfigError> {
+    app.state::<Profiles>()
+        .seal(&app.state::<Vault>(), &mut config.connection)
+        .await
+        .map_err(|message| {
+            ConfigError::Invalid(vec![FieldError {
+                field: "connection".to_string(),
+                message,
+            }])
+        })?;
+    config::save(&app, &config)?;
+
+    let backend = app.state::<BackendProcess>();
+    if let Err(e) = app
+        .state::<Profiles>()
+        .adopt(&app, &backend, config.connection)
+        .await
+    {
+        eprintln!("Failed to switch connection profile: {}", e);
+    }
+    let active = config.workspaces.active.clone();
+    if app.state::<Workspaces>().reload(config.workspaces).await {
+        let _ = app.emit_all("workspace://changed", &active);
+    }
+    if let Err(e) = logs::open_file(&app, &app.state::<BackendLogs>(), config.log_file) {
+        eprintln!("{}", e);
+    }
+    app.state::<Watchdog>().reload(config.restart).await;
+    app.state::<EventBridge>().reload(config.events).await;
+    app.state::<Notifier>().reload(config.notifications);
+    app.state::<Journal>().reload(config.journal);
+    app.state::<ResponseCache>().reload(&config.cache);
     Ok("Configuration saved successfully".to_string())
 }
 
 fn main() {
+    let backend_logs = BackendLogs::default();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
-        .manage(BackendProcess::default())
+        .manage(BackendProcess::new(backend_logs.clone()))
+        .manage(backend_logs)
+        .manage(Api::default())
+        .manage(Orphan::default())
+        .manage(Session::default())
+        .manage(Verifications::default())
+        .manage(Shutdown::default())
+        .manage(config::PendingWrites::default())
+        .manage(watcher::OwnWrites::default())
+        .setup(|app| {
+            app.manage(Profiles::load(&app.app_handle()));
+            app.manage(Watchdog::load(&app.app_handle()));
+            app.manage(Workspaces::load(&app.app_handle()));
+            app.manage(ResponseCache::load(&app.app_handle()));
+            app.manage(EventBridge::load(&app.app_handle()));
+            app.manage(Journal::load(&app.app_handle()));
+            app.manage(Notifier::load(&app.app_handle()));
+            app.manage(Vault::load(&app.app_handle()));
+            app.state::<BackendLogs>().attach(app.app_handle());
+            watchdog::spawn(app.app_handle());
+            health::spawn_monitor(app.app_handle());
+            lockfile::spawn_check(app.app_handle());
+            events::spawn(app.app_handle());
+            shutdown::spawn_signal_handler(app.app_handle());
+            tray::spawn(app.app_handle());
+            watcher::spawn(app.app_handle());
+            Ok(())
+        })
+        .system_tray(tray::build())
+        .on_system_tray_event(tray::on_event)
+        .on_window_event(shutdown::on_window_event)
         .invoke_handler(tauri::generate_handler![
             start_backend,
             stop_backend,
+            restart_backend,
             get_backend_status,
-            get_system_info,
+            resolve_backend_binary,
+            config_file::load_backend_config,
+            config_file::preview_backend_config,
+            config_file::save_backend_config,
+            api::list_models,
+            api::get_model,
+            api::verify_model,
+            api::list_providers,
+            session::login,
+            session::logout,
+            session::current_session,
+            verification::start_verification,
+            verification::stop_verification,
+            verification::verification_status,
+            events::get_event_bridge_status,
+            events::get_event_settings,
+            events::set_event_settings,
+            notifications::get_notification_settings,
+            notifications::save_notification_settings,
+            notifications::test_notification_rule,
+            journal::query_events,
+            journal::export_events,
+            journal::replay_events,
+            journal::stop_replay,
+            profile::get_connection_profiles,
+            profile::save_connection_profiles,
+            lockfile::get_orphaned_backend,
+            lockfile::adopt_orphaned_backend,
+            lockfile::terminate_orphaned_backend,
+            watchdog::get_restart_policy,
+            watchdog::set_restart_policy,
+            logs::get_backend_logs,
+            logs::configure_backend_log_file,
+            system::get_system_info,
             select_directory,
             select_file,
             save_file,
             load_config,
-            save_config
+            save_config,
+            shutdown::set_verification_in_progress,
+            tray::get_tray_options,
+            tray::set_tray_options,
+            vault::get_vault_status,
+            vault::unlock_vault,
+            vault::lock_vault,
+            vault::list_secrets,
+            vault::add_secret,
+            vault::rotate_secret,
+            vault::delete_secret,
+            workspace::list_workspaces,
+            workspace::create_workspace,
+            workspace::clone_workspace,
+            workspace::switch_workspace
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
\ No newline at end of file
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::ExitRequested { api, .. } = event {
+                // Keep the event loop alive until the backend is down.
+                api.prevent_exit();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    shutdown::run(&app).await;
+                    app.exit(0);
+                });
+            }
+        });
+}
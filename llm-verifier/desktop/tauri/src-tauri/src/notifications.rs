@@ -0,0 +1,402 @@
+//! OS notifications for backend events, raised according to the rules in the
+//! `notifications` section of the desktop config.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::api::notification::Notification;
+use tauri::{AppHandle, Manager, State};
+
+use crate::config;
+use crate::events::{BackendEvent, EventType, Severity};
+
+/// Local time range in which a rule stays silent. Wraps past midnight when
+/// `start` is later than `end`, e.g. `22:00`–`07:00`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// `HH:MM`, inclusive.
+    pub start: String,
+    /// `HH:MM`, exclusive.
+    pub end: String,
+}
+
+impl QuietHours {
+    fn parse(value: &str) -> Result<NaiveTime, String> {
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .map_err(|_| format!("Invalid quiet hours time `{}`, expected HH:MM", value))
+    }
+
+    fn contains(&self, time: NaiveTime) -> Result<bool, String> {
+        let start = Self::parse(&self.start)?;
+        let end = Self::parse(&self.end)?;
+        Ok(if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationRule {
+    pub name: String,
+    pub enabled: bool,
+    /// Event types the rule applies to; empty matches every type.
+    pub event_types: Vec<EventType>,
+    pub min_severity: Severity,
+    /// Models the rule applies to; empty matches events for any model.
+    pub model_ids: Vec<i64>,
+    /// Providers the rule applies to; empty matches events for any provider.
+    pub provider_ids: Vec<i64>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for NotificationRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            event_types: Vec::new(),
+            min_severity: Severity::Warning,
+            model_ids: Vec::new(),
+            provider_ids: Vec::new(),
+            quiet_hours: None,
+        }
+    }
+}
+
+impl NotificationRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(quiet) = &self.quiet_hours {
+            QuietHours::parse(&quiet.start)?;
+            QuietHours::parse(&quiet.end)?;
+        }
+        Ok(())
+    }
+
+    /// Reasons the rule does not fire for `event` at local time `at`; empty
+    /// when it fires.
+    fn rejections(&self, event: &BackendEvent, at: NaiveTime) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if !self.enabled {
+            reasons.push("Rule is disabled".to_string());
+        }
+        if !self.event_types.is_empty() && !self.event_types.contains(&event.event_type) {
+            reasons.push(format!(
+                "Event type `{}` is not selected",
+                event.event_type.as_str()
+            ));
+        }
+        if event.severity < self.min_severity {
+            reasons.push(format!(
+                "Severity {:?} is below {:?}",
+                event.severity, self.min_severity
+            ));
+        }
+        if !self.model_ids.is_empty()
+            && !event
+                .model_id
+                .map_or(false, |id| self.model_ids.contains(&id))
+        {
+            reasons.push("Event is not about a selected model".to_string());
+        }
+        if !self.provider_ids.is_empty()
+            && !event
+                .provider_id
+                .map_or(false, |id| self.provider_ids.contains(&id))
+        {
+            reasons.push("Event is not about a selected provider".to_string());
+        }
+        if let Some(quiet) = &self.quiet_hours {
+            match quiet.contains(at) {
+                Ok(true) => {
+                    reasons.push(format!("Within quiet hours {}-{}", quiet.start, quiet.end))
+                }
+                Ok(false) => {}
+                Err(e) => reasons.push(e),
+            }
+        }
+        reasons
+    }
+}
+
+/// The `notifications` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// Checked in order; the first rule that fires raises the notification.
+    pub rules: Vec<NotificationRule>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.notifications)
+            .unwrap_or_default()
+    }
+
+    /// The first rule that fires for `event` at local time `at`.
+    fn matching_rule(&self, event: &BackendEvent, at: NaiveTime) -> Option<&NotificationRule> {
+        if !self.enabled {
+            return None;
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.rejections(event, at).is_empty())
+    }
+}
+
+/// Managed state holding the notification settings, so events are matched
+/// without reading the config file.
+pub struct Notifier {
+    settings: RwLock<NotificationSettings>,
+}
+
+impl Notifier {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            settings: RwLock::new(NotificationSettings::load(app)),
+        }
+    }
+
+    pub fn reload(&self, settings: NotificationSettings) {
+        *self.settings.write().unwrap_or_else(|e| e.into_inner()) = settings;
+    }
+
+    fn settings(&self) -> NotificationSettings {
+        self.settings
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// Raises a notification for `event` if any rule matches it.
+pub fn notify(app: &AppHandle, event: &BackendEvent) {
+    let settings = app.state::<Notifier>().settings();
+    let rule = match settings.matching_rule(event, Local::now().time()) {
+        Some(rule) => rule,
+        None => return,
+    };
+
+    let result = Notification::new(&app.config().tauri.bundle.identifier)
+        .title(&event.title)
+        .body(&event.message)
+        .show();
+    if let Err(e) = result {
+        eprintln!(
+            "Failed to show notification for rule `{}`: {}",
+            rule.name, e
+        );
+    }
+}
+
+/// Result of [`test_notification_rule`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleEvaluation {
+    pub fires: bool,
+    /// Why the rule would not fire.
+    pub reasons: Vec<String>,
+    pub title: String,
+    pub body: String,
+}
+
+fn sample_event() -> BackendEvent {
+    BackendEvent {
+        id: "sample".to_string(),
+        event_type: EventType::VerificationCompleted,
+        severity: Severity::Warning,
+        title: "Verification Completed".to_string(),
+        message: "Sample verification finished".to_string(),
+        details: None,
+        model_id: None,
+        provider_id: None,
+        verification_id: None,
+        issue_id: None,
+        client_id: None,
+        user_id: None,
+        source: "desktop".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Dry-runs `rule` against `event` (a sample verification event when
+/// omitted) at local time `at` (now when omitted). Shows nothing.
+#[tauri::command]
+pub async fn test_notification_rule(
+    rule: NotificationRule,
+    event: Option<BackendEvent>,
+    at: Option<DateTime<Local>>,
+) -> Result<RuleEvaluation, String> {
+    rule.validate()?;
+    let event = event.unwrap_or_else(sample_event);
+    let reasons = rule.rejections(&event, at.unwrap_or_else(Local::now).time());
+    Ok(RuleEvaluation {
+        fires: reasons.is_empty(),
+        reasons,
+        title: event.title,
+        body: event.message,
+    })
+}
+
+#[tauri::command]
+pub async fn get_notification_settings(
+    notifier: State<'_, Notifier>,
+) -> Result<NotificationSettings, String> {
+    Ok(notifier.settings())
+}
+
+#[tauri::command]
+pub async fn save_notification_settings(
+    app: AppHandle,
+    notifier: State<'_, Notifier>,
+    settings: NotificationSettings,
+) -> Result<String, String> {
+    for rule in &settings.rules {
+        rule.validate()
+            .map_err(|e| format!("Rule `{}`: {}", rule.name, e))?;
+    }
+
+    config::update(&app, |config| config.notifications = settings.clone())
+        .map_err(|e| e.to_string())?;
+    notifier.reload(settings);
+    Ok("Notification rules saved".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    fn event(event_type: EventType, severity: Severity) -> BackendEvent {
+        BackendEvent {
+            event_type,
+            severity,
+            model_id: Some(7),
+            provider_id: Some(2),
+            ..sample_event()
+        }
+    }
+
+    fn quiet(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let night = quiet("22:00", "07:00");
+        for (at, expected) in [
+            ("21:59", false),
+            ("22:00", true),
+            ("23:30", true),
+            ("00:00", true),
+            ("06:59", true),
+            ("07:00", false),
+            ("12:00", false),
+        ] {
+            assert_eq!(night.contains(time(at)).unwrap(), expected, "{}", at);
+        }
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let lunch = quiet("12:00", "13:00");
+        assert!(!lunch.contains(time("11:59")).unwrap());
+        assert!(lunch.contains(time("12:00")).unwrap());
+        assert!(!lunch.contains(time("13:00")).unwrap());
+        assert!(quiet("25:00", "07:00").contains(time("12:00")).is_err());
+    }
+
+    #[test]
+    fn rule_filters_events() {
+        let rule = NotificationRule {
+            event_types: vec![EventType::VerificationFailed],
+            min_severity: Severity::Error,
+            model_ids: vec![7],
+            provider_ids: vec![2],
+            ..Default::default()
+        };
+        let noon = time("12:00");
+        assert!(rule
+            .rejections(&event(EventType::VerificationFailed, Severity::Error), noon)
+            .is_empty());
+        assert_eq!(
+            rule.rejections(&event(EventType::ScoreChanged, Severity::Info), noon)
+                .len(),
+            2
+        );
+
+        let other_model = BackendEvent {
+            model_id: Some(8),
+            provider_id: None,
+            ..event(EventType::VerificationFailed, Severity::Critical)
+        };
+        assert_eq!(rule.rejections(&other_model, noon).len(), 2);
+
+        let disabled = NotificationRule {
+            enabled: false,
+            ..rule.clone()
+        };
+        assert!(!disabled
+            .rejections(&event(EventType::VerificationFailed, Severity::Error), noon)
+            .is_empty());
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let settings = NotificationSettings {
+            enabled: true,
+            rules: vec![
+                NotificationRule {
+                    name: "night".to_string(),
+                    quiet_hours: Some(quiet("22:00", "07:00")),
+                    ..Default::default()
+                },
+                NotificationRule {
+                    name: "critical".to_string(),
+                    min_severity: Severity::Critical,
+                    ..Default::default()
+                },
+            ],
+        };
+        let critical = event(EventType::VerificationFailed, Severity::Critical);
+        let name = |at| {
+            settings
+                .matching_rule(&critical, time(at))
+                .map(|r| r.name.as_str())
+        };
+        assert_eq!(name("12:00"), Some("night"));
+        assert_eq!(name("23:00"), Some("critical"));
+        assert_eq!(
+            settings.matching_rule(
+                &event(EventType::VerificationFailed, Severity::Info),
+                time("12:00")
+            ),
+            None
+        );
+
+        let disabled = NotificationSettings {
+            enabled: false,
+            ..settings.clone()
+        };
+        assert!(disabled.matching_rule(&critical, time("12:00")).is_none());
+    }
+}
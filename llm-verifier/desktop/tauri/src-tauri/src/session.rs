@@ -0,0 +1,257 @@
+//! Login session against the backend API. The JWT stays in this process; the
+//! webview only ever sees [`SessionInfo`].
+
+use chrono::{DateTime, Utc};
+use llm_verifier_client::{Client, Token};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::api::{Api, ApiError};
+use crate::backend::profile::{ConnectionProfile, Profiles};
+use crate::backend::BackendProcess;
+
+/// What the webview is told about the logged-in user.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub username: String,
+    pub email: String,
+    pub role: String,
+    pub base_url: String,
+    pub logged_in_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Why a session ended without the user logging out; sent as `session://ended`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndReason {
+    /// The token's `exp` has passed.
+    Expired,
+    /// The backend answered 401 to a request carrying the token.
+    Rejected,
+    /// The managed backend was restarted or stopped, or the active profile
+    /// now points at another backend.
+    BackendChanged,
+}
+
+/// One run of one backend. Tokens are only sent to the run they were issued
+/// by, so a restarted managed backend starts logged out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendScope {
+    base_url: String,
+    started_at: Option<DateTime<Utc>>,
+}
+
+impl BackendScope {
+    /// Scope of the backend the active profile points at, or `None` when the
+    /// managed backend is not running.
+    pub async fn current(app: &AppHandle) -> Option<Self> {
+        let backend = app.state::<BackendProcess>();
+        let profiles = app.state::<Profiles>();
+        match profiles.active().await {
+            ConnectionProfile::Managed => {
+                let status = backend.status().await;
+                match (status.running, status.base_url) {
+                    (true, Some(base_url)) => Some(Self {
+                        base_url,
+                        started_at: status.started_at,
+                    }),
+                    _ => None,
+                }
+            }
+            ConnectionProfile::Remote { base_url, .. } => Some(Self {
+                base_url,
+                started_at: None,
+            }),
+        }
+    }
+}
+
+struct Active {
+    token: Token,
+    scope: BackendScope,
+    info: SessionInfo,
+}
+
+/// Managed state holding the logged-in session, if any.
+#[derive(Default)]
+pub struct Session(Mutex<Option<Active>>);
+
+impl Session {
+    /// Token to attach to requests sent to `scope`. A session issued by
+    /// another backend, or one that has expired, is ended instead.
+    pub async fn token(&self, app: &AppHandle, scope: &BackendScope) -> Option<Token> {
+        let mut active = self.0.lock().await;
+        let reason = match active.as_ref() {
+            None => return None,
+            Some(current) if &current.scope != scope => EndReason::BackendChanged,
+            Some(current) if current.token.is_expired() => EndReason::Expired,
+            Some(current) => return Some(current.token.clone()),
+        };
+        *active = None;
+        emit_ended(app, reason);
+        None
+    }
+
+    /// Ends the session, telling the webview to ask for a new login.
+    pub async fn end(&self, app: &AppHandle, reason: EndReason) {
+        if self.0.lock().await.take().is_some() {
+            emit_ended(app, reason);
+        }
+    }
+
+    async fn current(&self, app: &AppHandle) -> Option<SessionInfo> {
+        let mut active = self.0.lock().await;
+        let current = active.as_ref()?;
+        let reason = match BackendScope::current(app).await {
+            Some(scope) if scope != current.scope => EndReason::BackendChanged,
+            None => EndReason::BackendChanged,
+            Some(_) if current.token.is_expired() => EndReason::Expired,
+            Some(_) => return Some(current.info.clone()),
+        };
+        *active = None;
+        emit_ended(app, reason);
+        None
+    }
+}
+
+fn emit_ended(app: &AppHandle, reason: EndReason) {
+    println!("Session ended: {:?}", reason);
+    let _ = app.emit_all("session://ended", reason);
+}
+
+/// Logs `client` in to the backend run identified by `scope`.
+async fn open(
+    client: &Client,
+    scope: BackendScope,
+    username: &str,
+    password: &str,
+) -> Result<Active, ApiError> {
+    let auth = client.login(username, password).await?;
+    let token = client
+        .token()
+        .ok_or_else(|| ApiError::Login("The backend returned no token".to_string()))?;
+
+    let info = SessionInfo {
+        username: auth.user.username,
+        email: auth.user.email,
+        role: auth.user.role,
+        base_url: client.base_url().to_string(),
+        logged_in_at: Utc::now(),
+        expires_at: token.expires_at,
+    };
+    Ok(Active { token, scope, info })
+}
+
+/// Logs in to the backend the active profile points at. Replaces any
+/// existing session. `llm-verifier server` registers no login endpoint, so
+/// with the managed backend, or a remote profile using the `server` route
+/// layout, this fails with `login_unsupported` before sending anything;
+/// such remotes can still authenticate with their configured credentials.
+#[tauri::command]
+pub async fn login(
+    app: AppHandle,
+    api: State<'_, Api>,
+    session: State<'_, Session>,
+    username: String,
+    password: String,
+) -> Result<SessionInfo, ApiError> {
+    let (client, scope) = api.anonymous_client(&app).await?;
+    let active = open(&client, scope, &username, &password).await?;
+    let info = active.info.clone();
+    *session.0.lock().await = Some(active);
+    Ok(info)
+}
+
+/// Forgets the token. The server keeps no session state, so nothing is sent.
+#[tauri::command]
+pub async fn logout(session: State<'_, Session>) -> Result<(), String> {
+    *session.0.lock().await = None;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn current_session(
+    app: AppHandle,
+    session: State<'_, Session>,
+) -> Result<Option<SessionInfo>, String> {
+    Ok(session.current(&app).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::profile::{Auth, Connection, RouteLayout};
+    use serde_json::json;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A client built the way `Api` builds one for a remote profile.
+    fn client(server: &MockServer, routes: RouteLayout) -> Client {
+        Connection {
+            base_url: server.uri(),
+            auth: Auth::None,
+            routes,
+        }
+        .client(reqwest::Client::new())
+        .unwrap()
+    }
+
+    fn scope(server: &MockServer) -> BackendScope {
+        BackendScope {
+            base_url: server.uri(),
+            started_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn logs_in_through_a_v1_profile() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/auth/login"))
+            .and(body_json(
+                json!({ "username": "admin", "password": "secret" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "token": "issued",
+                "expires_at": "2100-01-01T00:00:00Z",
+                "user": { "id": 1, "username": "admin", "email": "admin@example.com", "role": "admin" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, RouteLayout::V1);
+        let active = open(&client, scope(&server), "admin", "secret")
+            .await
+            .unwrap();
+        assert_eq!(active.token.value, "issued");
+        assert_eq!(active.scope, scope(&server));
+        assert_eq!(active.info.username, "admin");
+        assert_eq!(active.info.role, "admin");
+        assert_eq!(
+            active.info.expires_at.map(|at| at.timestamp()),
+            Some(4_102_444_800)
+        );
+    }
+
+    #[tokio::test]
+    async fn server_profile_cannot_log_in() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, RouteLayout::Server);
+        let result = open(&client, scope(&server), "admin", "secret").await;
+        assert!(matches!(
+            result,
+            Err(ApiError::Client(
+                llm_verifier_client::Error::LoginUnsupported
+            ))
+        ));
+    }
+}
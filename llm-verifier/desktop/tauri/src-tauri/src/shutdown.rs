@@ -0,0 +1,193 @@
+//! Stops the managed backend when the app quits, the last window closes
+//! (unless the tray keeps the app running) or the process is asked to
+//! terminate.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::api::dialog;
+use tauri::{AppHandle, GlobalWindowEvent, Manager, State, Window, WindowEvent};
+use tokio::sync::Mutex;
+
+use crate::backend::{BackendError, BackendProcess};
+use crate::config;
+use crate::journal::Journal;
+use crate::tray::TrayOptions;
+
+/// Shutdown settings, read from the `shutdown` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutdownOptions {
+    /// Ask before closing the last window while a verification run is active.
+    pub confirm_while_verifying: bool,
+}
+
+impl Default for ShutdownOptions {
+    fn default() -> Self {
+        Self {
+            confirm_while_verifying: true,
+        }
+    }
+}
+
+impl ShutdownOptions {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.shutdown)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+pub struct Shutdown {
+    /// Reported by the frontend.
+    verifying: AtomicBool,
+    /// Set while jobs started with `start_verification` are unfinished.
+    jobs_running: AtomicBool,
+    /// Set once the user agreed to close despite a running verification.
+    confirmed: AtomicBool,
+    /// Whether the shutdown sequence already ran; held while it runs.
+    done: Mutex<bool>,
+}
+
+impl Shutdown {
+    pub fn set_jobs_running(&self, running: bool) {
+        self.jobs_running.store(running, Ordering::SeqCst);
+    }
+
+    fn is_verifying(&self) -> bool {
+        self.verifying.load(Ordering::SeqCst) || self.jobs_running.load(Ordering::SeqCst)
+    }
+}
+
+/// Lets the frontend report whether a verification run is in progress.
+#[tauri::command]
+pub async fn set_verification_in_progress(
+    shutdown: State<'_, Shutdown>,
+    active: bool,
+) -> Result<(), String> {
+    shutdown.verifying.store(active, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Asks for confirmation before the last window closes during a verification run.
+pub fn on_window_event(event: GlobalWindowEvent) {
+    let api = match event.event() {
+        WindowEvent::CloseRequested { api, .. } => api,
+        _ => return,
+    };
+    let window = event.window();
+    let app = window.app_handle();
+    let shutdown = app.state::<Shutdown>();
+
+    if TrayOptions::load(&app).keep_running_on_close {
+        // Quitting goes through the tray menu instead.
+        api.prevent_close();
+        if let Err(e) = window.hide() {
+            eprintln!("Failed to hide window: {}", e);
+        }
+        return;
+    }
+
+    if app.windows().len() > 1
+        || !shutdown.is_verifying()
+        || shutdown.confirmed.load(Ordering::SeqCst)
+        || !ShutdownOptions::load(&app).confirm_while_verifying
+    {
+        return;
+    }
+
+    api.prevent_close();
+    let target = window.clone();
+    dialog::ask(
+        Some(window),
+        "Verification in progress",
+        "A verification run is still in progress. Closing the app stops the backend and aborts it. Quit anyway?",
+        move |quit| {
+            if quit {
+                target.app_handle().state::<Shutdown>().confirmed.store(true, Ordering::SeqCst);
+                let _ = target.close();
+            }
+        },
+    );
+}
+
+/// Quits from the tray, asking first while a verification run is active.
+pub fn quit(app: &AppHandle) {
+    let confirm = app.state::<Shutdown>().is_verifying()
+        && ShutdownOptions::load(app).confirm_while_verifying;
+    let app = app.clone();
+    let exit = move |quit: bool| {
+        if quit {
+            tauri::async_runtime::spawn(async move {
+                run(&app).await;
+                app.exit(0);
+            });
+        }
+    };
+    if confirm {
+        dialog::ask(
+            None::<&Window>,
+            "Verification in progress",
+            "A verification run is still in progress. Quitting stops the backend and aborts it. Quit anyway?",
+            exit,
+        );
+    } else {
+        exit(true);
+    }
+}
+
+/// Stops the backend on SIGINT/SIGTERM (Ctrl+C on Windows) and exits.
+pub fn spawn_signal_handler(app: AppHandle) {
+    let on_interrupt = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            terminate(&on_interrupt, "SIGINT").await;
+        }
+    });
+
+    #[cfg(unix)]
+    tauri::async_runtime::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+                terminate(&app, "SIGTERM").await;
+            }
+            Err(e) => eprintln!("Failed to install SIGTERM handler: {}", e),
+        }
+    });
+}
+
+async fn terminate(app: &AppHandle, signal: &str) {
+    println!("Received {}, shutting down", signal);
+    run(app).await;
+    app.exit(0);
+}
+
+/// Stops the backend and waits for in-flight config and journal writes. Runs
+/// at most once; concurrent callers wait for the first one to finish.
+pub async fn run(app: &AppHandle) {
+    let shutdown = app.state::<Shutdown>();
+    let mut done = shutdown.done.lock().await;
+    if *done {
+        return;
+    }
+
+    match app.state::<BackendProcess>().stop().await {
+        Ok(exit) => println!("Backend stopped on shutdown (exit code {:?})", exit.code),
+        Err(BackendError::NotRunning) => {}
+        Err(BackendError::Stopping(_)) => {
+            // Another stop is under way; let it finish before exiting.
+            while app.state::<BackendProcess>().status().await.stopping {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+        Err(e) => eprintln!("Failed to stop backend on shutdown: {}", e),
+    }
+    config::flush(app);
+    app.state::<Journal>().flush();
+    *done = true;
+}
@@ -0,0 +1,171 @@
+//! Build metadata embedded by `build.rs` and runtime diagnostics for
+//! `get_system_info`.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use serde::Serialize;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, Pid, RefreshKind, System};
+use tauri::{AppHandle, State};
+
+use crate::backend::binary::{self, BinarySource};
+use crate::backend::launch::{self, LaunchOptions};
+use crate::backend::profile::{ConnectionProfile, Profiles};
+use crate::backend::BackendProcess;
+
+/// Compile-time facts about this build of the app.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub app_version: &'static str,
+    /// `rustc --version` of the compiler that built the app.
+    pub rustc: Option<&'static str>,
+    pub tauri: &'static str,
+    /// Short commit hash, suffixed with `-dirty` for uncommitted changes.
+    pub git_commit: Option<&'static str>,
+    /// Cargo profile, `debug` or `release`.
+    pub profile: &'static str,
+    pub target: &'static str,
+}
+
+impl BuildInfo {
+    fn current() -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION"),
+            rustc: non_empty(env!("BUILD_RUSTC_VERSION")),
+            tauri: tauri::VERSION,
+            git_commit: non_empty(env!("BUILD_GIT_COMMIT")),
+            profile: env!("BUILD_PROFILE"),
+            target: env!("BUILD_TARGET"),
+        }
+    }
+}
+
+/// `build.rs` embeds an empty string when it could not find a value.
+fn non_empty(value: &'static str) -> Option<&'static str> {
+    Some(value).filter(|v| !v.is_empty())
+}
+
+/// The machine the app runs on.
+#[derive(Debug, Clone, Serialize)]
+pub struct HostInfo {
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// e.g. `Linux 22.04 Ubuntu` or `Windows 11 Pro`.
+    pub os_release: Option<String>,
+    pub kernel_version: Option<String>,
+    pub cpu_count: usize,
+    pub physical_cores: Option<usize>,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+}
+
+/// The backend binary `start_backend` would launch and the running process.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendInfo {
+    pub binary_path: Option<PathBuf>,
+    pub binary_source: Option<BinarySource>,
+    /// Why no binary could be resolved.
+    pub binary_error: Option<String>,
+    /// Reported by `/api/health`, or by `--version` when the backend is down.
+    pub version: Option<String>,
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub uptime_secs: Option<i64>,
+    /// Resident set size of the backend process.
+    pub rss_bytes: Option<u64>,
+}
+
+/// Returned by `get_system_info`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub build: BuildInfo,
+    pub host: HostInfo,
+    pub app_data_dir: Option<PathBuf>,
+    pub backend: BackendInfo,
+}
+
+fn host_info(system: &System) -> HostInfo {
+    HostInfo {
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        os_release: System::long_os_version(),
+        kernel_version: System::kernel_version(),
+        cpu_count: system.cpus().len(),
+        physical_cores: system.physical_core_count(),
+        total_memory_bytes: system.total_memory(),
+        available_memory_bytes: system.available_memory(),
+    }
+}
+
+async fn backend_info(
+    app: &AppHandle,
+    backend: &BackendProcess,
+    profiles: &Profiles,
+    system: &mut System,
+) -> BackendInfo {
+    let resolution = LaunchOptions::load(app)
+        .map_err(|e| e.to_string())
+        .and_then(|options| {
+            binary::resolve(options.binary_path.as_deref()).map_err(|e| e.to_string())
+        });
+
+    let status = backend.status().await;
+    let health = match profiles.active().await {
+        ConnectionProfile::Managed => status.health.clone(),
+        ConnectionProfile::Remote { .. } => profiles.remote_health().await,
+    };
+    let mut version = health
+        .and_then(|h| h.report)
+        .map(|report| report.version)
+        .filter(|v| !v.is_empty());
+    if version.is_none() {
+        if let Ok(resolution) = &resolution {
+            version = launch::version(&resolution.path).await;
+        }
+    }
+
+    let rss_bytes = status.pid.and_then(|pid| {
+        let pid = Pid::from_u32(pid);
+        if system.refresh_process(pid) {
+            system.process(pid).map(|process| process.memory())
+        } else {
+            None
+        }
+    });
+
+    let (binary_path, binary_source, binary_error) = match resolution {
+        Ok(resolution) => (Some(resolution.path), Some(resolution.source), None),
+        Err(e) => (None, None, Some(e)),
+    };
+    BackendInfo {
+        binary_path,
+        binary_source,
+        binary_error,
+        version,
+        running: status.running,
+        pid: status.pid,
+        uptime_secs: status
+            .started_at
+            .map(|started| (Utc::now() - started).num_seconds()),
+        rss_bytes,
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_info(
+    app: AppHandle,
+    backend: State<'_, BackendProcess>,
+    profiles: State<'_, Profiles>,
+) -> Result<SystemInfo, String> {
+    let mut system = System::new_with_specifics(
+        RefreshKind::new()
+            .with_cpu(CpuRefreshKind::new())
+            .with_memory(MemoryRefreshKind::new().with_ram()),
+    );
+    Ok(SystemInfo {
+        build: BuildInfo::current(),
+        host: host_info(&system),
+        app_data_dir: app.path_resolver().app_data_dir(),
+        backend: backend_info(&app, &backend, &profiles, &mut system).await,
+    })
+}
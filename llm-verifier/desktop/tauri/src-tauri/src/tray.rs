@@ -0,0 +1,372 @@
+//! System tray icon reflecting backend health, with quick actions for the
+//! backend, verification and config export.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::api::notification::Notification;
+use tauri::{
+    AppHandle, CustomMenuItem, Icon, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem,
+};
+
+use crate::backend::binary;
+use crate::backend::health::HealthState;
+use crate::backend::launch::LaunchOptions;
+use crate::backend::profile::{ConnectionProfile, Profiles};
+use crate::backend::BackendProcess;
+use crate::config;
+use crate::shutdown;
+use crate::verification::{self, ModelSelector};
+
+/// How often the tray re-reads the backend status.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Edge length of the generated status icon, in pixels.
+const ICON_SIZE: u32 = 32;
+
+const STATUS: &str = "status";
+const SHOW: &str = "show";
+const START: &str = "start";
+const STOP: &str = "stop";
+const RESTART: &str = "restart";
+const VERIFY_ALL: &str = "verify_all";
+const EXPORT_OPENCODE: &str = "export_opencode";
+const OPEN_LOGS: &str = "open_logs";
+const QUIT: &str = "quit";
+
+/// Tray settings, read from the `tray` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayOptions {
+    /// Hide the window instead of quitting when it is closed. Quitting from
+    /// the tray still confirms and stops the backend.
+    pub keep_running_on_close: bool,
+}
+
+impl Default for TrayOptions {
+    fn default() -> Self {
+        Self {
+            keep_running_on_close: true,
+        }
+    }
+}
+
+impl TrayOptions {
+    pub fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.tray)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrayState {
+    Stopped,
+    Starting,
+    Healthy,
+    Degraded,
+}
+
+impl TrayState {
+    fn label(self) -> &'static str {
+        match self {
+            TrayState::Stopped => "Stopped",
+            TrayState::Starting => "Starting",
+            TrayState::Healthy => "Healthy",
+            TrayState::Degraded => "Degraded",
+        }
+    }
+
+    fn color(self) -> [u8; 3] {
+        match self {
+            TrayState::Stopped => [0x9e, 0x9e, 0x9e],
+            TrayState::Starting => [0xf5, 0xa6, 0x23],
+            TrayState::Healthy => [0x2e, 0xcc, 0x71],
+            TrayState::Degraded => [0xe7, 0x4c, 0x3c],
+        }
+    }
+
+    /// A filled circle in the state's colour on a transparent background.
+    fn icon(self) -> Icon {
+        let [r, g, b] = self.color();
+        let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+        let radius = ICON_SIZE as f32 / 2.0 - 2.0;
+        let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+        for y in 0..ICON_SIZE {
+            for x in 0..ICON_SIZE {
+                let distance = (x as f32 - center).hypot(y as f32 - center);
+                // One pixel of falloff keeps the edge from looking jagged.
+                let alpha = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                rgba.extend_from_slice(&[r, g, b, (alpha * 255.0) as u8]);
+            }
+        }
+        Icon::Rgba {
+            rgba,
+            width: ICON_SIZE,
+            height: ICON_SIZE,
+        }
+    }
+}
+
+/// What the tray shows for the active connection profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Snapshot {
+    state: TrayState,
+    managed: bool,
+    running: bool,
+}
+
+async fn snapshot(app: &AppHandle) -> Snapshot {
+    let profiles = app.state::<Profiles>();
+    match profiles.active().await {
+        ConnectionProfile::Managed => {
+            let status = app.state::<BackendProcess>().status().await;
+            let state = match (status.running, status.health.map(|h| h.state)) {
+                (false, _) => TrayState::Stopped,
+                (true, None) | (true, Some(HealthState::Starting)) => TrayState::Starting,
+                (true, Some(HealthState::Healthy)) => TrayState::Healthy,
+                (true, Some(_)) => TrayState::Degraded,
+            };
+            Snapshot {
+                state,
+                managed: true,
+                running: status.running,
+            }
+        }
+        ConnectionProfile::Remote { .. } => {
+            let state = match profiles.remote_health().await.map(|h| h.state) {
+                None | Some(HealthState::Starting) => TrayState::Starting,
+                Some(HealthState::Healthy) => TrayState::Healthy,
+                Some(HealthState::Degraded) => TrayState::Degraded,
+                Some(HealthState::Unreachable) => TrayState::Stopped,
+            };
+            Snapshot {
+                state,
+                managed: false,
+                running: false,
+            }
+        }
+    }
+}
+
+/// The tray as it looks before the first status refresh.
+pub fn build() -> SystemTray {
+    let item = |id: &str, title: &str| CustomMenuItem::new(id, title);
+    let menu = SystemTrayMenu::new()
+        .add_item(item(STATUS, "Backend: Stopped").disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(item(SHOW, "Show Window"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(item(START, "Start Backend"))
+        .add_item(item(STOP, "Stop Backend").disabled())
+        .add_item(item(RESTART, "Restart Backend").disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(item(VERIFY_ALL, "Verify All Models Now"))
+        .add_item(item(EXPORT_OPENCODE, "Export OpenCode Config"))
+        .add_item(item(OPEN_LOGS, "Open Log Folder"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(item(QUIT, "Quit"));
+
+    SystemTray::new()
+        .with_icon(TrayState::Stopped.icon())
+        .with_tooltip("LLM Verifier: backend stopped")
+        .with_menu(menu)
+}
+
+/// Keeps the tray icon, tooltip and menu in sync with the backend for the
+/// lifetime of the app.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut shown: Option<Snapshot> = None;
+        loop {
+            let current = snapshot(&app).await;
+            if shown != Some(current) {
+                apply(&app, current);
+                shown = Some(current);
+            }
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+fn apply(app: &AppHandle, snapshot: Snapshot) {
+    let tray = app.tray_handle();
+    let label = snapshot.state.label();
+    let result = tray
+        .set_icon(snapshot.state.icon())
+        .and_then(|_| tray.set_tooltip(&format!("LLM Verifier: backend {}", label.to_lowercase())))
+        .and_then(|_| {
+            tray.get_item(STATUS)
+                .set_title(format!("Backend: {}", label))
+        })
+        .and_then(|_| {
+            tray.get_item(START)
+                .set_enabled(snapshot.managed && !snapshot.running)
+        })
+        .and_then(|_| tray.get_item(STOP).set_enabled(snapshot.running))
+        .and_then(|_| tray.get_item(RESTART).set_enabled(snapshot.running));
+    if let Err(e) = result {
+        eprintln!("Failed to update tray: {}", e);
+    }
+}
+
+/// Handles clicks on the tray icon and its menu.
+pub fn on_event(app: &AppHandle, event: SystemTrayEvent) {
+    let id = match event {
+        SystemTrayEvent::LeftClick { .. } => return show_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => id,
+        _ => return,
+    };
+    if id == SHOW {
+        return show_window(app);
+    }
+    if id == QUIT {
+        return shutdown::quit(app);
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match id.as_str() {
+            START => start(&app).await,
+            STOP => stop(&app).await,
+            RESTART => match stop(&app).await {
+                Ok(_) => start(&app).await,
+                Err(e) => Err(e),
+            },
+            VERIFY_ALL => verify_all(&app).await,
+            EXPORT_OPENCODE => export_opencode(&app).await,
+            OPEN_LOGS => open_logs(&app),
+            _ => return,
+        };
+        match result {
+            Ok(message) => notify(&app, "LLM Verifier", &message),
+            Err(e) => notify(&app, "LLM Verifier: action failed", &e),
+        }
+        apply(&app, snapshot(&app).await);
+    });
+}
+
+fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    let result = Notification::new(&app.config().tauri.bundle.identifier)
+        .title(title)
+        .body(body)
+        .show();
+    if let Err(e) = result {
+        eprintln!("Failed to show tray notification: {}", e);
+    }
+}
+
+async fn start(app: &AppHandle) -> Result<String, String> {
+    crate::launch_managed(app).await.map_err(|e| e.to_string())
+}
+
+async fn stop(app: &AppHandle) -> Result<String, String> {
+    crate::stop_backend(app.state(), app.state())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn verify_all(app: &AppHandle) -> Result<String, String> {
+    let job = verification::start_verification(
+        app.clone(),
+        app.state(),
+        app.state(),
+        app.state(),
+        vec![ModelSelector::Name("all".to_string())],
+        None,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(format!(
+        "Verifying {} models (job {})",
+        job.models.len(),
+        job.job_id
+    ))
+}
+
+/// Runs `llm-verifier ai-config export opencode` into the downloads folder.
+async fn export_opencode(app: &AppHandle) -> Result<String, String> {
+    let options = LaunchOptions::load(app).map_err(|e| e.to_string())?;
+    let program = binary::resolve(options.binary_path.as_deref())
+        .map_err(|e| e.to_string())?
+        .path;
+    let dir = tauri::api::path::download_dir()
+        .or_else(|| app.path_resolver().app_data_dir())
+        .ok_or("Failed to resolve a download directory")?;
+    let output = dir.join("opencode_config.json");
+
+    let args = [
+        "ai-config".to_string(),
+        "export".to_string(),
+        "opencode".to_string(),
+        output.display().to_string(),
+    ];
+    let result = options
+        .command(&program, &args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program.display(), e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let stdout = String::from_utf8_lossy(&result.stdout);
+        // The Go CLI reports most failures on stdout.
+        let detail = stderr.lines().chain(stdout.lines()).last().unwrap_or("");
+        return Err(format!("Export exited with {}: {}", result.status, detail));
+    }
+    Ok(format!("OpenCode config exported to {}", output.display()))
+}
+
+fn open_logs(app: &AppHandle) -> Result<String, String> {
+    let dir = log_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    reveal(&dir)?;
+    Ok(format!("Opened {}", dir.display()))
+}
+
+/// Where `configure_backend_log_file` writes the rotated backend log.
+fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or("Failed to resolve app data directory")?
+        .join("logs"))
+}
+
+/// Opens `dir` in the platform file manager. The shell `open` API is scoped
+/// to URLs, so this goes through the platform opener directly.
+fn reveal(dir: &Path) -> Result<(), String> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener)
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {}: {}", opener, e))
+}
+
+#[tauri::command]
+pub async fn get_tray_options(app: AppHandle) -> Result<TrayOptions, String> {
+    Ok(TrayOptions::load(&app))
+}
+
+#[tauri::command]
+pub async fn set_tray_options(app: AppHandle, options: TrayOptions) -> Result<String, String> {
+    config::update(&app, |config| config.tray = options).map_err(|e| e.to_string())?;
+    Ok("Tray settings saved".to_string())
+}
@@ -0,0 +1,710 @@
+//! Encrypted store for provider API keys, kept in `vault.json` in the app
+//! data dir.
+//!
+//! The vault key is derived from a passphrase with Argon2id and only held in
+//! memory while the vault is unlocked. Each secret is sealed with
+//! XChaCha20-Poly1305, using its name as associated data so ciphertexts
+//! cannot be swapped between entries. Decrypted values are never returned to
+//! the webview; they only reach the backend process as environment variables,
+//! or a remote backend as the credentials of a connection profile.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use tauri::{AppHandle, Manager, State};
+use zeroize::Zeroizing;
+
+use crate::config;
+
+const VAULT_FILE_NAME: &str = "vault.json";
+
+/// Format version of `vault.json`.
+const VAULT_VERSION: u32 = 1;
+
+/// Sealed with the vault key when the vault is created, to tell a wrong
+/// passphrase apart from a corrupt secret.
+const CHECK_PLAINTEXT: &[u8] = b"llm-verifier-vault";
+const CHECK_AAD: &[u8] = b"check";
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+type VaultKey = Zeroizing<[u8; KEY_LEN]>;
+
+/// Argon2id cost parameters, stored with the vault so they can be raised
+/// later without locking out existing files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    /// Hex-encoded.
+    salt: String,
+}
+
+impl KdfParams {
+    fn generate() -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+            salt: hex::encode(salt),
+        }
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<VaultKey, VaultError> {
+        let salt = hex::decode(&self.salt).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LEN),
+        )
+        .map_err(|e| VaultError::Corrupt(format!("Invalid key derivation parameters: {}", e)))?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| VaultError::Crypto(e.to_string()))?;
+        Ok(key)
+    }
+}
+
+/// A nonce and ciphertext, both hex-encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Sealed {
+    fn seal(key: &VaultKey, plaintext: &[u8], aad: &[u8]) -> Result<Self, VaultError> {
+        let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|e| VaultError::Crypto(e.to_string()))?;
+        Ok(Self {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// `None` when the key or associated data does not match.
+    fn open(&self, key: &VaultKey, aad: &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>, VaultError> {
+        let nonce = hex::decode(&self.nonce).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+        let ciphertext =
+            hex::decode(&self.ciphertext).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+        if nonce.len() != 24 {
+            return Err(VaultError::Corrupt(format!(
+                "Nonce is {} bytes, expected 24",
+                nonce.len()
+            )));
+        }
+        let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+        Ok(cipher
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad,
+                },
+            )
+            .ok()
+            .map(Zeroizing::new))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredSecret {
+    #[serde(flatten)]
+    sealed: Sealed,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Contents of `vault.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    check: Sealed,
+    /// Keyed by the environment variable the secret is exported as.
+    secrets: BTreeMap<String, StoredSecret>,
+}
+
+/// A secret as listed to the UI; the value itself is masked.
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretSummary {
+    pub name: String,
+    /// e.g. `sk-…9f2c`.
+    pub masked: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Returned by `get_vault_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultStatus {
+    /// Whether a vault has been created yet.
+    pub exists: bool,
+    pub unlocked: bool,
+    pub secret_count: usize,
+}
+
+/// Errors surfaced by the vault commands.
+#[derive(Debug, thiserror::Error)]
+pub enum VaultError {
+    #[error("{0}")]
+    Io(String),
+    #[error("The vault is locked")]
+    Locked,
+    #[error("Wrong vault passphrase")]
+    WrongPassphrase,
+    #[error("The passphrase must be at least {} characters", MIN_PASSPHRASE_LEN)]
+    WeakPassphrase,
+    #[error("`{0}` is not a valid environment variable name")]
+    InvalidName(String),
+    #[error("A secret named `{0}` already exists")]
+    AlreadyExists(String),
+    #[error("No secret named `{0}`")]
+    NotFound(String),
+    #[error("The secret value must not be empty")]
+    EmptyValue,
+    #[error("Vault file is corrupt: {0}")]
+    Corrupt(String),
+    #[error("Encryption failed: {0}")]
+    Crypto(String),
+}
+
+impl VaultError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::Locked => "locked",
+            Self::WrongPassphrase => "wrong_passphrase",
+            Self::WeakPassphrase => "weak_passphrase",
+            Self::InvalidName(_) => "invalid_name",
+            Self::AlreadyExists(_) => "already_exists",
+            Self::NotFound(_) => "not_found",
+            Self::EmptyValue => "empty_value",
+            Self::Corrupt(_) => "corrupt",
+            Self::Crypto(_) => "crypto",
+        }
+    }
+}
+
+// Commands hand errors to the webview as `{ kind, message, details }`.
+impl Serialize for VaultError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::InvalidName(name) | Self::AlreadyExists(name) | Self::NotFound(name) => {
+                map.serialize_entry("details", name)?
+            }
+            _ => map.serialize_entry("details", &())?,
+        }
+        map.end()
+    }
+}
+
+/// Managed state: where the vault lives and, while unlocked, its key.
+pub struct Vault {
+    path: Option<PathBuf>,
+    key: Mutex<Option<VaultKey>>,
+}
+
+// The key must never end up in a log.
+impl fmt::Debug for Vault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vault")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Vault {
+    /// Starts locked; nothing is read until the vault is used.
+    pub fn load(app: &AppHandle) -> Self {
+        Self::at(
+            app.path_resolver()
+                .app_data_dir()
+                .map(|dir| dir.join(VAULT_FILE_NAME)),
+        )
+    }
+
+    pub(crate) fn at(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            key: Mutex::new(None),
+        }
+    }
+
+    fn path(&self) -> Result<&Path, VaultError> {
+        self.path
+            .as_deref()
+            .ok_or_else(|| VaultError::Io("Failed to resolve app data directory".to_string()))
+    }
+
+    fn read(&self) -> Result<Option<VaultFile>, VaultError> {
+        let path = self.path()?;
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(VaultError::Io(format!("Failed to read vault: {}", e))),
+        };
+        let file: VaultFile =
+            serde_json::from_str(&contents).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+        if file.version > VAULT_VERSION {
+            return Err(VaultError::Corrupt(format!(
+                "Version {} was written by a newer app",
+                file.version
+            )));
+        }
+        Ok(Some(file))
+    }
+
+    /// Replaces `vault.json`, readable by the owner only.
+    fn write(&self, file: &VaultFile) -> Result<(), VaultError> {
+        let path = self.path()?;
+        let contents =
+            serde_json::to_vec_pretty(file).map_err(|e| VaultError::Io(e.to_string()))?;
+        // A new file starts with the default permissions; later writes keep
+        // the restricted ones.
+        config::write_atomic(path, &contents)
+            .map_err(|e| VaultError::Io(format!("Failed to write vault: {}", e)))?;
+        restrict_permissions(path);
+        Ok(())
+    }
+
+    fn key(&self) -> std::sync::MutexGuard<'_, Option<VaultKey>> {
+        self.key.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn status(&self) -> Result<VaultStatus, VaultError> {
+        let file = self.read()?;
+        Ok(VaultStatus {
+            exists: file.is_some(),
+            unlocked: self.key().is_some(),
+            secret_count: file.map_or(0, |file| file.secrets.len()),
+        })
+    }
+
+    /// Unlocks the vault, creating it with `passphrase` if there is none yet.
+    /// Slow on purpose; call it off the async runtime.
+    pub(crate) fn unlock(&self, passphrase: &str) -> Result<VaultStatus, VaultError> {
+        match self.read()? {
+            Some(file) => {
+                let key = file.kdf.derive(passphrase)?;
+                if file.check.open(&key, CHECK_AAD)?.is_none() {
+                    return Err(VaultError::WrongPassphrase);
+                }
+                *self.key() = Some(key);
+            }
+            None => {
+                if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                    return Err(VaultError::WeakPassphrase);
+                }
+                let kdf = KdfParams::generate();
+                let key = kdf.derive(passphrase)?;
+                self.write(&VaultFile {
+                    version: VAULT_VERSION,
+                    kdf,
+                    check: Sealed::seal(&key, CHECK_PLAINTEXT, CHECK_AAD)?,
+                    secrets: BTreeMap::new(),
+                })?;
+                println!("Created secret vault");
+                *self.key() = Some(key);
+            }
+        }
+        self.status()
+    }
+
+    pub(crate) fn lock(&self) {
+        *self.key() = None;
+    }
+
+    /// Runs `change` on the unlocked vault file and saves it.
+    fn modify<F>(&self, change: F) -> Result<(), VaultError>
+    where
+        F: FnOnce(&VaultKey, &mut VaultFile) -> Result<(), VaultError>,
+    {
+        let guard = self.key();
+        let key = guard.as_ref().ok_or(VaultError::Locked)?;
+        let mut file = self.read()?.ok_or(VaultError::Locked)?;
+        change(key, &mut file)?;
+        self.write(&file)
+    }
+
+    fn list(&self) -> Result<Vec<SecretSummary>, VaultError> {
+        let guard = self.key();
+        let key = guard.as_ref().ok_or(VaultError::Locked)?;
+        let file = self.read()?.ok_or(VaultError::Locked)?;
+        file.secrets
+            .iter()
+            .map(|(name, secret)| {
+                let value = open_secret(key, name, secret)?;
+                Ok(SecretSummary {
+                    name: name.clone(),
+                    masked: mask(&value),
+                    created_at: secret.created_at,
+                    updated_at: secret.updated_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Seals `value` under `name`. With `replace`, the secret must already
+    /// exist; otherwise it must not.
+    fn put(&self, name: &str, value: &str, replace: bool) -> Result<(), VaultError> {
+        if !is_env_name(name) {
+            return Err(VaultError::InvalidName(name.to_string()));
+        }
+        if value.is_empty() {
+            return Err(VaultError::EmptyValue);
+        }
+        self.modify(|key, file| {
+            let now = Utc::now();
+            let created_at = match (file.secrets.get(name), replace) {
+                (Some(_), false) => return Err(VaultError::AlreadyExists(name.to_string())),
+                (None, true) => return Err(VaultError::NotFound(name.to_string())),
+                (Some(existing), true) => existing.created_at,
+                (None, false) => now,
+            };
+            file.secrets.insert(
+                name.to_string(),
+                StoredSecret {
+                    sealed: Sealed::seal(key, value.as_bytes(), name.as_bytes())?,
+                    created_at,
+                    updated_at: now,
+                },
+            );
+            Ok(())
+        })
+    }
+
+    /// Seals `value` under `name`, adding or replacing it.
+    pub fn store(&self, name: &str, value: &str) -> Result<(), VaultError> {
+        let exists = self
+            .read()?
+            .map_or(false, |file| file.secrets.contains_key(name));
+        self.put(name, value, exists)
+    }
+
+    /// Decrypts the secret `name`.
+    pub fn secret(&self, name: &str) -> Result<Zeroizing<String>, VaultError> {
+        let file = self.read()?.ok_or(VaultError::Locked)?;
+        let secret = file
+            .secrets
+            .get(name)
+            .ok_or_else(|| VaultError::NotFound(name.to_string()))?;
+        let guard = self.key();
+        let key = guard.as_ref().ok_or(VaultError::Locked)?;
+        open_secret(key, name, secret)
+    }
+
+    /// The masked value of `name`, or bullets when it cannot be read, e.g.
+    /// while the vault is locked.
+    pub fn masked(&self, name: &str) -> String {
+        self.secret(name)
+            .map(|value| mask(&value))
+            .unwrap_or_else(|_| mask(""))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), VaultError> {
+        self.modify(|_, file| {
+            file.secrets
+                .remove(name)
+                .map(|_| ())
+                .ok_or_else(|| VaultError::NotFound(name.to_string()))
+        })
+    }
+
+    /// The secrets `wanted` selects, as environment variables for the
+    /// backend process. Only fails when one of them cannot be decrypted, e.g.
+    /// because the vault is locked.
+    pub fn backend_env<F>(&self, wanted: F) -> Result<Vec<(String, String)>, VaultError>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let file = match self.read()? {
+            Some(file) => file,
+            None => return Ok(Vec::new()),
+        };
+        let secrets: Vec<_> = file
+            .secrets
+            .iter()
+            .filter(|(name, _)| wanted(name))
+            .collect();
+        if secrets.is_empty() {
+            return Ok(Vec::new());
+        }
+        let guard = self.key();
+        let key = guard.as_ref().ok_or(VaultError::Locked)?;
+        secrets
+            .into_iter()
+            .map(|(name, secret)| Ok((name.clone(), open_secret(key, name, secret)?.to_string())))
+            .collect()
+    }
+}
+
+fn open_secret(
+    key: &VaultKey,
+    name: &str,
+    secret: &StoredSecret,
+) -> Result<Zeroizing<String>, VaultError> {
+    let plaintext = secret
+        .sealed
+        .open(key, name.as_bytes())?
+        .ok_or_else(|| VaultError::Corrupt(format!("`{}` failed authentication", name)))?;
+    String::from_utf8(plaintext.to_vec())
+        .map(Zeroizing::new)
+        .map_err(|_| VaultError::Corrupt(format!("`{}` is not valid UTF-8", name)))
+}
+
+/// Keeps just enough of a value to tell keys apart.
+pub(crate) fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 12 {
+        return "•".repeat(8);
+    }
+    let head: String = chars[..3].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(0o600)) {
+        eprintln!("Failed to restrict vault permissions: {}", e);
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {}
+
+#[tauri::command]
+pub async fn get_vault_status(vault: State<'_, Vault>) -> Result<VaultStatus, VaultError> {
+    vault.status()
+}
+
+/// Unlocks the vault, or creates it when none exists yet.
+#[tauri::command]
+pub async fn unlock_vault(app: AppHandle, passphrase: String) -> Result<VaultStatus, VaultError> {
+    let passphrase = Zeroizing::new(passphrase);
+    tauri::async_runtime::spawn_blocking(move || app.state::<Vault>().unlock(&passphrase))
+        .await
+        .map_err(|e| VaultError::Io(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn lock_vault(vault: State<'_, Vault>) -> Result<VaultStatus, VaultError> {
+    vault.lock();
+    vault.status()
+}
+
+#[tauri::command]
+pub async fn list_secrets(vault: State<'_, Vault>) -> Result<Vec<SecretSummary>, VaultError> {
+    vault.list()
+}
+
+#[tauri::command]
+pub async fn add_secret(
+    vault: State<'_, Vault>,
+    name: String,
+    value: String,
+) -> Result<(), VaultError> {
+    let value = Zeroizing::new(value);
+    vault.put(&name, &value, false)?;
+    println!("Added secret {}", name);
+    Ok(())
+}
+
+/// Replaces the value of an existing secret.
+#[tauri::command]
+pub async fn rotate_secret(
+    vault: State<'_, Vault>,
+    name: String,
+    value: String,
+) -> Result<(), VaultError> {
+    let value = Zeroizing::new(value);
+    vault.put(&name, &value, true)?;
+    println!("Rotated secret {}", name);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_secret(vault: State<'_, Vault>, name: String) -> Result<(), VaultError> {
+    vault.delete(&name)?;
+    println!("Deleted secret {}", name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    /// A vault file in a fresh directory under the system temp dir.
+    fn scratch_vault(test: &str) -> Vault {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-vault-{}-{}",
+            std::process::id(),
+            test
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        Vault::at(Some(dir.join(VAULT_FILE_NAME)))
+    }
+
+    fn random_key() -> VaultKey {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        OsRng.fill_bytes(key.as_mut());
+        key
+    }
+
+    #[test]
+    fn sealed_round_trips() {
+        let key = random_key();
+        let sealed = Sealed::seal(&key, b"sk-secret", b"OPENAI_API_KEY").unwrap();
+        let opened = sealed.open(&key, b"OPENAI_API_KEY").unwrap().unwrap();
+        assert_eq!(opened.as_slice(), b"sk-secret");
+        assert!(sealed
+            .open(&random_key(), b"OPENAI_API_KEY")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn sealed_is_bound_to_its_name() {
+        let key = random_key();
+        let sealed = Sealed::seal(&key, b"sk-secret", b"OPENAI_API_KEY").unwrap();
+        assert!(sealed.open(&key, b"ANTHROPIC_API_KEY").unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase() {
+        let vault = scratch_vault("wrong-passphrase");
+        assert!(matches!(
+            vault.unlock("short"),
+            Err(VaultError::WeakPassphrase)
+        ));
+        assert!(vault.unlock(PASSPHRASE).unwrap().unlocked);
+        vault.put("OPENAI_API_KEY", "sk-secret", false).unwrap();
+        vault.lock();
+
+        assert!(matches!(
+            vault.unlock("incorrect horse battery"),
+            Err(VaultError::WrongPassphrase)
+        ));
+        assert!(!vault.status().unwrap().unlocked);
+        assert!(matches!(vault.list(), Err(VaultError::Locked)));
+
+        vault.unlock(PASSPHRASE).unwrap();
+        let env = vault.backend_env(|_| true).unwrap();
+        assert_eq!(
+            env,
+            vec![("OPENAI_API_KEY".to_string(), "sk-secret".to_string())]
+        );
+    }
+
+    #[test]
+    fn secret_moved_to_another_name_fails() {
+        let vault = scratch_vault("moved-secret");
+        vault.unlock(PASSPHRASE).unwrap();
+        vault.put("OPENAI_API_KEY", "sk-secret", false).unwrap();
+
+        let mut file = vault.read().unwrap().unwrap();
+        let secret = file.secrets.remove("OPENAI_API_KEY").unwrap();
+        file.secrets.insert("ANTHROPIC_API_KEY".to_string(), secret);
+        vault.write(&file).unwrap();
+
+        assert!(matches!(vault.list(), Err(VaultError::Corrupt(_))));
+        assert!(matches!(
+            vault.backend_env(|_| true),
+            Err(VaultError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn rotate_replaces_the_value() {
+        let vault = scratch_vault("rotate");
+        vault.unlock(PASSPHRASE).unwrap();
+        assert!(matches!(
+            vault.put("OPENAI_API_KEY", "sk-new", true),
+            Err(VaultError::NotFound(_))
+        ));
+        vault
+            .put("OPENAI_API_KEY", "sk-old-0000000", false)
+            .unwrap();
+        assert!(matches!(
+            vault.put("OPENAI_API_KEY", "sk-other", false),
+            Err(VaultError::AlreadyExists(_))
+        ));
+        let before = vault.list().unwrap().remove(0);
+
+        vault.put("OPENAI_API_KEY", "sk-new-1111111", true).unwrap();
+        let after = vault.list().unwrap().remove(0);
+        assert_eq!(after.created_at, before.created_at);
+        assert!(after.updated_at >= before.updated_at);
+        assert_eq!(after.masked, "sk-…1111");
+        assert_eq!(vault.backend_env(|_| true).unwrap()[0].1, "sk-new-1111111");
+    }
+
+    #[test]
+    fn locked_vault_only_blocks_wanted_secrets() {
+        let vault = scratch_vault("locked-env");
+        assert!(vault.backend_env(|_| true).unwrap().is_empty());
+        vault.unlock(PASSPHRASE).unwrap();
+        vault.put("OPENAI_API_KEY", "sk-secret", false).unwrap();
+        vault.lock();
+
+        assert!(matches!(
+            vault.backend_env(|_| true),
+            Err(VaultError::Locked)
+        ));
+        assert!(vault
+            .backend_env(|name| name == "ANTHROPIC_API_KEY")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn validates_names_and_masks_values() {
+        assert!(is_env_name("OPENAI_API_KEY"));
+        assert!(is_env_name("_key1"));
+        assert!(!is_env_name("1KEY"));
+        assert!(!is_env_name("OPENAI-KEY"));
+        assert!(!is_env_name(""));
+        assert_eq!(mask("short"), "••••••••");
+        assert_eq!(mask("sk-abcdefgh9f2c"), "sk-…9f2c");
+    }
+}
@@ -0,0 +1,767 @@
+//! Verification jobs started from the app. The server verifies one model per
+//! request, so a job submits its models with bounded concurrency and then
+//! polls each one until the backend records a verification newer than the
+//! submission.
+//!
+//! `POST /api/models/{id}/verify` only inserts a `running` result and no
+//! endpoint reports when it finishes. A model completes here once its
+//! `last_verified` moves past the submission, which only happens when the
+//! model is verified by something other than that endpoint. Otherwise it
+//! ends up `submitted` after `verification.model_timeout_secs`: the backend
+//! accepted it, but the app cannot tell how it went.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use llm_verifier_client::types::{Model, ModelDetails, Verification};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::api::{self, Api, ApiError};
+use crate::config;
+use crate::shutdown::Shutdown;
+
+/// Page size used to resolve model selectors.
+const MODEL_PAGE_SIZE: u32 = 100;
+
+/// Polling settings, read from the `verification` section of the desktop
+/// config when a job starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerificationOptions {
+    /// Delay between checks of a submitted model.
+    pub poll_interval_secs: u64,
+    /// Time a submitted model is tracked before it is reported `submitted`.
+    pub model_timeout_secs: u64,
+}
+
+impl Default for VerificationOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 5,
+            model_timeout_secs: 60,
+        }
+    }
+}
+
+impl VerificationOptions {
+    fn load(app: &AppHandle) -> Self {
+        config::load(app)
+            .map(|config| config.verification)
+            .unwrap_or_default()
+    }
+
+    fn polling(&self) -> Polling {
+        Polling {
+            interval: Duration::from_secs(self.poll_interval_secs),
+            timeout: Duration::from_secs(self.model_timeout_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Polling {
+    interval: Duration,
+    timeout: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl Priority {
+    /// Models of the job submitted and polled at once.
+    fn concurrency(self) -> usize {
+        match self {
+            Self::Low => 1,
+            Self::Normal => 2,
+            Self::High => 4,
+        }
+    }
+}
+
+/// A model to verify: its numeric id, its `model_id` or name, or `"all"`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ModelSelector {
+    Id(i64),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelState {
+    Queued,
+    Submitting,
+    /// Submitted; waiting for the backend to record the result.
+    Running,
+    /// Accepted by the backend, but no result was recorded before the
+    /// timeout. The outcome is unknown rather than failed.
+    Submitted,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl ModelState {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            Self::Submitted | Self::Completed | Self::Failed | Self::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelProgress {
+    pub model_id: i64,
+    pub name: String,
+    pub state: ModelState,
+    /// Verification result created by the backend on submission.
+    pub verification_id: Option<i64>,
+    /// Verification status and score recorded by the backend once completed.
+    pub status: Option<String>,
+    pub score: Option<f64>,
+    pub error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ModelProgress {
+    fn queued(model: &Model) -> Self {
+        Self {
+            model_id: model.id,
+            name: model.name.clone(),
+            state: ModelState::Queued,
+            verification_id: None,
+            status: None,
+            score: None,
+            error: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub job_id: u64,
+    pub priority: Priority,
+    pub started_at: DateTime<Utc>,
+    /// Set once every model has finished.
+    pub finished_at: Option<DateTime<Utc>>,
+    pub models: Vec<ModelProgress>,
+}
+
+/// Payload of `verification://progress`, sent on every model state change.
+#[derive(Debug, Clone, Serialize)]
+struct ProgressEvent {
+    job_id: u64,
+    model: ModelProgress,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    #[error(transparent)]
+    Api(#[from] ApiError),
+    #[error("No model matches `{0}`")]
+    UnknownModel(String),
+    #[error("No models to verify")]
+    NoModels,
+    #[error("Unknown verification job {0}")]
+    UnknownJob(u64),
+    #[error("Model {model_id} is not part of verification job {job_id}")]
+    UnknownJobModel { job_id: u64, model_id: i64 },
+}
+
+// Same `{ kind, message, details }` shape as `ApiError`, which is passed through.
+impl Serialize for VerificationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let kind = match self {
+            Self::Api(e) => return e.serialize(serializer),
+            Self::UnknownModel(_) => "unknown_model",
+            Self::NoModels => "no_models",
+            Self::UnknownJob(_) => "unknown_job",
+            Self::UnknownJobModel { .. } => "unknown_job_model",
+        };
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("kind", kind)?;
+        map.serialize_entry("message", &self.to_string())?;
+        map.serialize_entry("details", &())?;
+        map.end()
+    }
+}
+
+/// Managed state tracking the jobs started in this session.
+#[derive(Default)]
+pub struct Verifications {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, JobStatus>>,
+}
+
+impl Verifications {
+    async fn job(&self, job_id: u64) -> Result<JobStatus, VerificationError> {
+        self.jobs
+            .lock()
+            .await
+            .get(&job_id)
+            .cloned()
+            .ok_or(VerificationError::UnknownJob(job_id))
+    }
+
+    /// Applies `change` to a model that has not finished yet and passes the
+    /// new state to `report`. Returns `false` when the model already
+    /// finished, e.g. because it was cancelled meanwhile.
+    async fn update<R, F>(&self, report: &R, job_id: u64, model_id: i64, change: F) -> bool
+    where
+        R: Fn(ProgressEvent),
+        F: FnOnce(&mut ModelProgress),
+    {
+        let mut jobs = self.jobs.lock().await;
+        let model = match jobs
+            .get_mut(&job_id)
+            .and_then(|job| job.models.iter_mut().find(|m| m.model_id == model_id))
+        {
+            Some(model) if !model.state.is_finished() => model,
+            _ => return false,
+        };
+        change(model);
+        model.updated_at = Utc::now();
+        report(ProgressEvent {
+            job_id,
+            model: model.clone(),
+        });
+        true
+    }
+
+    /// Cancels one model of a job, or every unfinished model when `model_id`
+    /// is omitted.
+    async fn cancel<R>(
+        &self,
+        report: &R,
+        job_id: u64,
+        model_id: Option<i64>,
+    ) -> Result<JobStatus, VerificationError>
+    where
+        R: Fn(ProgressEvent),
+    {
+        let job = self.job(job_id).await?;
+        let targets: Vec<i64> = match model_id {
+            Some(id) if job.models.iter().any(|m| m.model_id == id) => vec![id],
+            Some(id) => {
+                return Err(VerificationError::UnknownJobModel {
+                    job_id,
+                    model_id: id,
+                })
+            }
+            None => job.models.iter().map(|m| m.model_id).collect(),
+        };
+        for id in targets {
+            self.update(report, job_id, id, |m| m.state = ModelState::Cancelled)
+                .await;
+        }
+        self.job(job_id).await
+    }
+
+    async fn is_cancelled(&self, job_id: u64, model_id: i64) -> bool {
+        self.jobs
+            .lock()
+            .await
+            .get(&job_id)
+            .and_then(|job| job.models.iter().find(|m| m.model_id == model_id))
+            .map_or(true, |m| m.state == ModelState::Cancelled)
+    }
+
+    async fn finish(&self, app: &AppHandle, job_id: u64) {
+        let mut jobs = self.jobs.lock().await;
+        if let Some(job) = jobs.get_mut(&job_id) {
+            job.finished_at = Some(Utc::now());
+        }
+        let active = jobs.values().any(|job| job.finished_at.is_none());
+        app.state::<Shutdown>().set_jobs_running(active);
+    }
+}
+
+/// Resolves the selectors against the backend's model list, keeping their
+/// order and dropping duplicates.
+async fn resolve_models(
+    app: &AppHandle,
+    api: &Api,
+    selectors: &[ModelSelector],
+) -> Result<Vec<Model>, VerificationError> {
+    let client = api.client(app).await?;
+    let result = client
+        .models(&Default::default(), MODEL_PAGE_SIZE)
+        .try_collect()
+        .await;
+    let available = api::settle(app, result).await?;
+
+    let mut selected: Vec<Model> = Vec::new();
+    for selector in selectors {
+        let matches: Vec<&Model> = match selector {
+            ModelSelector::Name(name) if name == "all" => available.iter().collect(),
+            ModelSelector::Id(id) => available.iter().filter(|m| m.id == *id).collect(),
+            ModelSelector::Name(name) => available
+                .iter()
+                .filter(|m| &m.model_id == name || &m.name == name)
+                .collect(),
+        };
+        if matches.is_empty() {
+            return Err(VerificationError::UnknownModel(match selector {
+                ModelSelector::Id(id) => id.to_string(),
+                ModelSelector::Name(name) => name.clone(),
+            }));
+        }
+        for model in matches {
+            if !selected.iter().any(|m| m.id == model.id) {
+                selected.push(model.clone());
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// Sends progress to the webview as `verification://progress`.
+fn emitter(app: &AppHandle) -> impl Fn(ProgressEvent) + '_ {
+    move |event| {
+        let _ = app.emit_all("verification://progress", event);
+    }
+}
+
+/// Takes models off the job's queue until it is empty.
+async fn run_worker(
+    app: AppHandle,
+    job_id: u64,
+    polling: Polling,
+    queue: Arc<std::sync::Mutex<VecDeque<i64>>>,
+) {
+    loop {
+        let model_id = match queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front() {
+            Some(model_id) => model_id,
+            None => return,
+        };
+        verify(&app, job_id, model_id, polling).await;
+    }
+}
+
+async fn verify(app: &AppHandle, job_id: u64, model_id: i64, polling: Polling) {
+    let api = app.state::<Api>().inner();
+    let submit = || async move {
+        let client = api.client(app).await?;
+        api::settle(app, client.verify_model(model_id).await).await
+    };
+    let check = || async move {
+        let client = api.client(app).await?;
+        api::settle(app, client.get_model(model_id).await).await
+    };
+    track(
+        app.state::<Verifications>().inner(),
+        &emitter(app),
+        job_id,
+        model_id,
+        polling,
+        submit,
+        check,
+    )
+    .await;
+}
+
+/// Submits a model with `submit`, then polls it with `check` until the
+/// backend records a newer verification, the model is cancelled or
+/// `polling.timeout` passes, which leaves it `Submitted`.
+async fn track<R, S, SF, C, CF>(
+    jobs: &Verifications,
+    report: &R,
+    job_id: u64,
+    model_id: i64,
+    polling: Polling,
+    submit: S,
+    check: C,
+) where
+    R: Fn(ProgressEvent),
+    S: FnOnce() -> SF,
+    SF: Future<Output = Result<Verification, ApiError>>,
+    C: Fn() -> CF,
+    CF: Future<Output = Result<ModelDetails, ApiError>>,
+{
+    let fail = |error: String| async move {
+        jobs.update(report, job_id, model_id, |m| {
+            m.state = ModelState::Failed;
+            m.error = Some(error);
+        })
+        .await;
+    };
+
+    if !jobs
+        .update(report, job_id, model_id, |m| {
+            m.state = ModelState::Submitting
+        })
+        .await
+    {
+        return;
+    }
+    let verification = match submit().await {
+        Ok(verification) => verification,
+        Err(e) => return fail(e.to_string()).await,
+    };
+    let submitted_at = verification.started_at;
+    if !jobs
+        .update(report, job_id, model_id, |m| {
+            m.state = ModelState::Running;
+            m.verification_id = Some(verification.verification_id);
+        })
+        .await
+    {
+        return;
+    }
+
+    let deadline = Instant::now() + polling.timeout;
+    loop {
+        tokio::time::sleep(polling.interval).await;
+        if jobs.is_cancelled(job_id, model_id).await {
+            return;
+        }
+        if Instant::now() >= deadline {
+            jobs.update(report, job_id, model_id, |m| {
+                m.state = ModelState::Submitted
+            })
+            .await;
+            return;
+        }
+
+        // Transient failures are retried until the deadline.
+        let details = match check().await {
+            Ok(details) => details,
+            Err(_) => continue,
+        };
+        if details.last_verified.map_or(false, |at| at >= submitted_at) {
+            jobs.update(report, job_id, model_id, |m| {
+                m.state = ModelState::Completed;
+                m.status = Some(details.model.status.clone());
+                m.score = Some(details.model.score);
+            })
+            .await;
+            return;
+        }
+    }
+}
+
+/// Starts verifying `models` (ids, names or `"all"`) and returns the queued job.
+#[tauri::command]
+pub async fn start_verification(
+    app: AppHandle,
+    api: State<'_, Api>,
+    jobs: State<'_, Verifications>,
+    shutdown: State<'_, Shutdown>,
+    models: Vec<ModelSelector>,
+    priority: Option<Priority>,
+) -> Result<JobStatus, VerificationError> {
+    let priority = priority.unwrap_or_default();
+    let selected = resolve_models(&app, &api, &models).await?;
+    if selected.is_empty() {
+        return Err(VerificationError::NoModels);
+    }
+
+    let job_id = jobs.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+    let status = JobStatus {
+        job_id,
+        priority,
+        started_at: Utc::now(),
+        finished_at: None,
+        models: selected.iter().map(ModelProgress::queued).collect(),
+    };
+    jobs.jobs.lock().await.insert(job_id, status.clone());
+    shutdown.set_jobs_running(true);
+
+    let polling = VerificationOptions::load(&app).polling();
+
+    let queue = Arc::new(std::sync::Mutex::new(
+        selected.iter().map(|m| m.id).collect::<VecDeque<_>>(),
+    ));
+    let workers: Vec<_> = (0..priority.concurrency().min(selected.len()))
+        .map(|_| {
+            tauri::async_runtime::spawn(run_worker(app.clone(), job_id, polling, queue.clone()))
+        })
+        .collect();
+    tauri::async_runtime::spawn(async move {
+        for worker in workers {
+            let _ = worker.await;
+        }
+        app.state::<Verifications>().finish(&app, job_id).await;
+    });
+
+    Ok(status)
+}
+
+/// Cancels one model of a job, or every unfinished model when `model_id` is
+/// omitted. The server cannot abort a submitted verification; the app only
+/// stops submitting and tracking it.
+#[tauri::command]
+pub async fn stop_verification(
+    app: AppHandle,
+    jobs: State<'_, Verifications>,
+    job_id: u64,
+    model_id: Option<i64>,
+) -> Result<JobStatus, VerificationError> {
+    jobs.cancel(&emitter(&app), job_id, model_id).await
+}
+
+#[tauri::command]
+pub async fn verification_status(
+    jobs: State<'_, Verifications>,
+    job_id: u64,
+) -> Result<JobStatus, VerificationError> {
+    jobs.job(job_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_verifier_client::{Client, Routes};
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SUBMITTED_AT: &str = "2024-01-02T10:00:00Z";
+
+    const FAST: Polling = Polling {
+        interval: Duration::from_millis(10),
+        timeout: Duration::from_secs(5),
+    };
+
+    fn model(id: i64, last_verified: Option<&str>) -> serde_json::Value {
+        json!({
+            "id": id,
+            "model_id": format!("model-{}", id),
+            "name": format!("Model {}", id),
+            "provider": "openai",
+            "provider_id": 1,
+            "status": "verified",
+            "score": 87.5,
+            "capabilities": ["text"],
+            "description": "",
+            "version": "1",
+            "deprecated": false,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+            "last_verified": last_verified
+        })
+    }
+
+    async fn mock_model(server: &MockServer, id: i64, checks_before_result: Option<u64>) {
+        Mock::given(method("POST"))
+            .and(path(format!("/api/models/{}/verify", id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "verification_started",
+                "model_id": id,
+                "verification_id": id * 10,
+                "started_at": SUBMITTED_AT
+            })))
+            .mount(server)
+            .await;
+        let stale = Mock::given(method("GET"))
+            .and(path(format!("/api/models/{}", id)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(model(id, Some("2024-01-01T00:00:00Z"))),
+            );
+        match checks_before_result {
+            Some(n) => {
+                stale.up_to_n_times(n).mount(server).await;
+                Mock::given(method("GET"))
+                    .and(path(format!("/api/models/{}", id)))
+                    .respond_with(
+                        ResponseTemplate::new(200)
+                            .set_body_json(model(id, Some("2024-01-02T10:05:00Z"))),
+                    )
+                    .mount(server)
+                    .await;
+            }
+            None => stale.mount(server).await,
+        }
+    }
+
+    async fn start_job(jobs: &Verifications, ids: &[i64]) -> u64 {
+        let models = ids
+            .iter()
+            .map(|&id| serde_json::from_value(model(id, None)).unwrap())
+            .collect::<Vec<Model>>();
+        jobs.jobs.lock().await.insert(
+            1,
+            JobStatus {
+                job_id: 1,
+                priority: Priority::Normal,
+                started_at: Utc::now(),
+                finished_at: None,
+                models: models.iter().map(ModelProgress::queued).collect(),
+            },
+        );
+        1
+    }
+
+    async fn run(
+        jobs: &Verifications,
+        report: &impl Fn(ProgressEvent),
+        client: &Client,
+        model_id: i64,
+        polling: Polling,
+    ) {
+        let submit = || async move { Ok(client.verify_model(model_id).await?) };
+        let check = || async move { Ok(client.get_model(model_id).await?) };
+        track(jobs, report, 1, model_id, polling, submit, check).await;
+    }
+
+    /// States reported so far, by model.
+    type Events = Arc<std::sync::Mutex<Vec<(i64, ModelState)>>>;
+
+    fn recorder() -> (Events, impl Fn(ProgressEvent)) {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let report = move |event: ProgressEvent| {
+            sink.lock()
+                .unwrap()
+                .push((event.model.model_id, event.model.state));
+        };
+        (events, report)
+    }
+
+    fn client(server: &MockServer) -> Client {
+        Client::builder(server.uri())
+            .routes(Routes::SERVER)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn completes_once_the_backend_records_a_newer_result() {
+        let server = MockServer::start().await;
+        mock_model(&server, 1, Some(2)).await;
+        let jobs = Verifications::default();
+        let job_id = start_job(&jobs, &[1]).await;
+        let (events, report) = recorder();
+
+        run(&jobs, &report, &client(&server), 1, FAST).await;
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                (1, ModelState::Submitting),
+                (1, ModelState::Running),
+                (1, ModelState::Completed)
+            ]
+        );
+        let model = jobs.job(job_id).await.unwrap().models.remove(0);
+        assert_eq!(model.verification_id, Some(10));
+        assert_eq!(model.status.as_deref(), Some("verified"));
+        assert_eq!(model.score, Some(87.5));
+    }
+
+    #[tokio::test]
+    async fn fails_when_submission_is_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/models/1/verify"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Database not available"))
+            .mount(&server)
+            .await;
+        let jobs = Verifications::default();
+        let job_id = start_job(&jobs, &[1]).await;
+        let (events, report) = recorder();
+
+        run(&jobs, &report, &client(&server), 1, FAST).await;
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(1, ModelState::Submitting), (1, ModelState::Failed)]
+        );
+        let model = jobs.job(job_id).await.unwrap().models.remove(0);
+        assert!(model.error.is_some());
+        assert_eq!(model.verification_id, None);
+    }
+
+    #[tokio::test]
+    async fn reports_submitted_when_no_result_is_recorded() {
+        let server = MockServer::start().await;
+        mock_model(&server, 1, None).await;
+        let jobs = Verifications::default();
+        let job_id = start_job(&jobs, &[1]).await;
+        let (events, report) = recorder();
+        let polling = Polling {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(50),
+        };
+
+        run(&jobs, &report, &client(&server), 1, polling).await;
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                (1, ModelState::Submitting),
+                (1, ModelState::Running),
+                (1, ModelState::Submitted)
+            ]
+        );
+        let model = jobs.job(job_id).await.unwrap().models.remove(0);
+        assert_eq!(model.verification_id, Some(10));
+        assert_eq!(model.error, None);
+        // Finished as far as the job is concerned, so it cannot be cancelled.
+        let job = jobs.cancel(&report, job_id, Some(1)).await.unwrap();
+        assert_eq!(job.models[0].state, ModelState::Submitted);
+    }
+
+    #[tokio::test]
+    async fn cancels_a_single_model() {
+        let server = MockServer::start().await;
+        mock_model(&server, 1, Some(3)).await;
+        mock_model(&server, 2, None).await;
+        let jobs = Verifications::default();
+        let job_id = start_job(&jobs, &[1, 2]).await;
+        let (events, report) = recorder();
+        let client = client(&server);
+
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            jobs.cancel(&report, job_id, Some(2)).await.unwrap()
+        };
+        let (_, _, cancelled) = tokio::join!(
+            run(&jobs, &report, &client, 1, FAST),
+            run(&jobs, &report, &client, 2, FAST),
+            cancel
+        );
+
+        assert_eq!(cancelled.models[1].state, ModelState::Cancelled);
+        let job = jobs.job(job_id).await.unwrap();
+        assert_eq!(job.models[0].state, ModelState::Completed);
+        assert_eq!(job.models[1].state, ModelState::Cancelled);
+        // Nothing is reported for the model after it was cancelled.
+        let last_of_model_2 = events
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == 2)
+            .copied();
+        assert_eq!(last_of_model_2, Some((2, ModelState::Cancelled)));
+
+        assert!(matches!(
+            jobs.cancel(&report, job_id, Some(3)).await,
+            Err(VerificationError::UnknownJobModel { .. })
+        ));
+        // Cancelling the whole job leaves finished models alone.
+        let job = jobs.cancel(&report, job_id, None).await.unwrap();
+        assert_eq!(job.models[0].state, ModelState::Completed);
+    }
+}
@@ -0,0 +1,527 @@
+//! Watches the desktop config and the backend's `config.yaml` for edits made
+//! outside the app, and emits `config://changed` with what changed so the
+//! window can reload instead of showing stale values.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+
+use crate::backend::config_file;
+use crate::backend::launch::LaunchOptions;
+use crate::backend::BackendProcess;
+use crate::config::{self, DesktopConfig, FieldError};
+
+/// Quiet period after the last file event before the files are re-read, so
+/// an editor's write-then-rename counts as one change.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Delay before retrying a directory that could not be watched, doubled on
+/// every failure up to [`MAX_RETRY`].
+const INITIAL_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(60);
+
+/// Settings only read when the backend starts, per file.
+const DESKTOP_LAUNCH_KEYS: &[&str] = &["backend", "workspaces"];
+const BACKEND_LAUNCH_KEYS: &[&str] = &["api.port", "database.path", "llms"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFileKind {
+    /// The app's own `config.json`.
+    Desktop,
+    /// The backend's `config.yaml`.
+    Backend,
+}
+
+/// One value that differs between the old and new file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValueChange {
+    /// Dotted path, e.g. `api.port` or `llms[0].endpoint`.
+    pub path: String,
+    /// `None` when the value was added.
+    pub before: Option<Value>,
+    /// `None` when the value was removed.
+    pub after: Option<Value>,
+}
+
+/// Payload of `config://changed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChanged {
+    pub file: ConfigFileKind,
+    pub path: PathBuf,
+    pub changes: Vec<ValueChange>,
+    /// Validation errors of the new contents; empty when valid.
+    pub errors: Vec<FieldError>,
+    /// Whether the running managed backend only picks the change up after a
+    /// restart, e.g. through `restart_backend`.
+    pub restart_required: bool,
+}
+
+/// What the app itself last wrote to each config file, so its own saves are
+/// not reported as outside edits.
+#[derive(Default)]
+pub struct OwnWrites(Mutex<HashMap<PathBuf, String>>);
+
+impl OwnWrites {
+    /// Records `text` as written by the app; call before writing `path`.
+    pub fn record(&self, path: &Path, text: &str) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_path_buf(), text.to_string());
+    }
+
+    fn contains(&self, path: &Path, text: &str) -> bool {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(path)
+            .map_or(false, |written| written == text)
+    }
+}
+
+/// Outcome of re-reading a watched file.
+enum Refresh {
+    Unchanged,
+    /// The app wrote the new contents itself.
+    Own,
+    Changed(Vec<ValueChange>, Vec<FieldError>),
+}
+
+/// Last seen contents of a watched file.
+struct Snapshot {
+    kind: ConfigFileKind,
+    path: PathBuf,
+    text: Option<String>,
+    values: Value,
+}
+
+impl Snapshot {
+    fn new(kind: ConfigFileKind, path: PathBuf, options: &LaunchOptions) -> Self {
+        let text = fs::read_to_string(&path).ok();
+        let values = text
+            .as_deref()
+            .and_then(|text| parse(kind, &path, text, options).ok())
+            .map_or(Value::Null, |(values, _)| values);
+        Self {
+            kind,
+            path,
+            text,
+            values,
+        }
+    }
+
+    /// Re-reads the file and reports what changed since the last read.
+    fn refresh(&mut self, options: &LaunchOptions, own: &OwnWrites) -> Refresh {
+        let text = fs::read_to_string(&self.path).ok();
+        if text == self.text {
+            return Refresh::Unchanged;
+        }
+        self.text = text;
+        let (values, errors) = match &self.text {
+            Some(text) => match parse(self.kind, &self.path, text, options) {
+                Ok(parsed) => parsed,
+                // Keep the last good values so the next diff is against them.
+                Err(e) => {
+                    let error = FieldError {
+                        field: String::new(),
+                        message: e,
+                    };
+                    return Refresh::Changed(Vec::new(), vec![error]);
+                }
+            },
+            None => (Value::Null, Vec::new()),
+        };
+        let mut changes = Vec::new();
+        diff("", &self.values, &values, &mut changes);
+        self.values = values;
+        match &self.text {
+            Some(text) if own.contains(&self.path, text) => Refresh::Own,
+            _ => Refresh::Changed(changes, errors),
+        }
+    }
+}
+
+/// Parses and validates a watched file. `options` supplies the environment
+/// `config.yaml` placeholders are expanded with.
+fn parse(
+    kind: ConfigFileKind,
+    path: &Path,
+    text: &str,
+    options: &LaunchOptions,
+) -> Result<(Value, Vec<FieldError>), String> {
+    match kind {
+        ConfigFileKind::Desktop => {
+            let values: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+            let errors = match serde_json::from_value::<DesktopConfig>(values.clone()) {
+                Ok(config) => config.validate(),
+                Err(e) => vec![FieldError {
+                    field: String::new(),
+                    message: e.to_string(),
+                }],
+            };
+            Ok((values, errors))
+        }
+        ConfigFileKind::Backend => {
+            let file = config_file::inspect(path, true, text.to_string(), options)
+                .map_err(|e| e.to_string())?;
+            Ok((file.values, file.errors))
+        }
+    }
+}
+
+/// Collects the leaves that differ between `before` and `after`.
+fn diff(path: &str, before: &Value, after: &Value, changes: &mut Vec<ValueChange>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            for key in keys {
+                let path = join(key);
+                match (before.get(key), after.get(key)) {
+                    (Some(b), Some(a)) => diff(&path, b, a, changes),
+                    (b, a) => changes.push(ValueChange {
+                        path,
+                        before: b.cloned(),
+                        after: a.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            for i in 0..before.len().max(after.len()) {
+                let path = format!("{}[{}]", path, i);
+                match (before.get(i), after.get(i)) {
+                    (Some(b), Some(a)) => diff(&path, b, a, changes),
+                    (b, a) => changes.push(ValueChange {
+                        path,
+                        before: b.cloned(),
+                        after: a.cloned(),
+                    }),
+                }
+            }
+        }
+        (before, after) if before == after => {}
+        (before, after) => changes.push(ValueChange {
+            path: path.to_string(),
+            before: Some(before.clone()).filter(|v| !v.is_null()),
+            after: Some(after.clone()).filter(|v| !v.is_null()),
+        }),
+    }
+}
+
+fn affects_launch(kind: ConfigFileKind, changes: &[ValueChange]) -> bool {
+    let keys = match kind {
+        ConfigFileKind::Desktop => DESKTOP_LAUNCH_KEYS,
+        ConfigFileKind::Backend => BACKEND_LAUNCH_KEYS,
+    };
+    changes.iter().any(|change| {
+        keys.iter().any(|key| {
+            change.path == *key
+                || change.path.starts_with(&format!("{}.", key))
+                || change.path.starts_with(&format!("{}[", key))
+        })
+    })
+}
+
+/// The files to watch: the desktop config and the `config.yaml` the managed
+/// backend is started with, which moves with the active workspace.
+fn watched_paths(app: &AppHandle, options: &LaunchOptions) -> Vec<(ConfigFileKind, PathBuf)> {
+    let mut paths = Vec::new();
+    match config::path(app) {
+        Ok(path) => paths.push((ConfigFileKind::Desktop, path)),
+        Err(e) => eprintln!("Not watching the desktop config: {}", e),
+    }
+    match config_file::config_path(options) {
+        Ok(path) => paths.push((ConfigFileKind::Backend, path)),
+        Err(e) => eprintln!("Not watching the backend config: {}", e),
+    }
+    paths
+}
+
+/// Watches `dir`, creating it first: the config dirs only appear with the
+/// first save, and a missing dir cannot be watched.
+fn watch_dir(watcher: &mut RecommendedWatcher, dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())
+}
+
+/// Points `snapshots` and the watcher at the current paths. Directories are
+/// watched rather than files, so replacing a file by rename is still seen.
+/// Returns `false` when a directory could not be watched and should be
+/// retried.
+fn rewatch(
+    app: &AppHandle,
+    watcher: &mut RecommendedWatcher,
+    dirs: &mut BTreeSet<PathBuf>,
+    snapshots: &mut Vec<Snapshot>,
+) -> bool {
+    let options = LaunchOptions::load(app).unwrap_or_default();
+    let paths = watched_paths(app, &options);
+    snapshots.retain(|snapshot| paths.contains(&(snapshot.kind, snapshot.path.clone())));
+    for (kind, path) in paths {
+        if !snapshots.iter().any(|s| s.kind == kind) {
+            snapshots.push(Snapshot::new(kind, path, &options));
+        }
+    }
+
+    let wanted: BTreeSet<PathBuf> = snapshots
+        .iter()
+        .filter_map(|snapshot| snapshot.path.parent().map(Path::to_path_buf))
+        .collect();
+    for dir in dirs.difference(&wanted) {
+        let _ = watcher.unwatch(dir);
+    }
+    dirs.retain(|dir| wanted.contains(dir));
+    let mut complete = true;
+    for dir in wanted {
+        if dirs.contains(&dir) {
+            continue;
+        }
+        match watch_dir(watcher, &dir) {
+            Ok(()) => {
+                dirs.insert(dir);
+            }
+            Err(e) => {
+                eprintln!("Failed to watch {}: {}", dir.display(), e);
+                complete = false;
+            }
+        }
+    }
+    complete
+}
+
+/// Starts the watcher. Changes are emitted as `config://changed`.
+pub fn spawn(app: AppHandle) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = match notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Config files will not be watched: {}", e);
+            return;
+        }
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut dirs = BTreeSet::new();
+        let mut snapshots = Vec::new();
+        let mut complete = rewatch(&app, &mut watcher, &mut dirs, &mut snapshots);
+        let mut retry = INITIAL_RETRY;
+
+        loop {
+            let event = if complete {
+                retry = INITIAL_RETRY;
+                rx.recv().await
+            } else {
+                match tokio::time::timeout(retry, rx.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        retry = (retry * 2).min(MAX_RETRY);
+                        complete = rewatch(&app, &mut watcher, &mut dirs, &mut snapshots);
+                        continue;
+                    }
+                }
+            };
+            let event = match event {
+                Some(event) => event,
+                None => return,
+            };
+            if let Err(e) = event {
+                eprintln!("Config watcher error: {}", e);
+                continue;
+            }
+            // Wait for the burst of events from one save to settle.
+            loop {
+                match tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+
+            let options = LaunchOptions::load(&app).unwrap_or_default();
+            let running = app.state::<BackendProcess>().status().await.running;
+            let own = app.state::<OwnWrites>();
+            let mut desktop_changed = false;
+            for snapshot in &mut snapshots {
+                let refresh = snapshot.refresh(&options, &own);
+                desktop_changed |= snapshot.kind == ConfigFileKind::Desktop
+                    && !matches!(refresh, Refresh::Unchanged);
+                let (changes, errors) = match refresh {
+                    Refresh::Changed(changes, errors) => (changes, errors),
+                    Refresh::Unchanged | Refresh::Own => continue,
+                };
+                println!(
+                    "{} changed on disk ({} values)",
+                    snapshot.path.display(),
+                    changes.len()
+                );
+                let payload = ConfigChanged {
+                    file: snapshot.kind,
+                    path: snapshot.path.clone(),
+                    restart_required: running && affects_launch(snapshot.kind, &changes),
+                    changes,
+                    errors,
+                };
+                let _ = app.emit_all("config://changed", payload);
+            }
+            // The desktop config decides which `config.yaml` is in use, also
+            // after the app changed it itself, e.g. by switching workspaces.
+            if desktop_changed {
+                complete = rewatch(&app, &mut watcher, &mut dirs, &mut snapshots);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn changes(before: Value, after: Value) -> Vec<(String, Option<Value>, Option<Value>)> {
+        let mut changes = Vec::new();
+        diff("", &before, &after, &mut changes);
+        changes
+            .into_iter()
+            .map(|c| (c.path, c.before, c.after))
+            .collect()
+    }
+
+    fn change(path: &str) -> ValueChange {
+        ValueChange {
+            path: path.to_string(),
+            before: None,
+            after: Some(json!(1)),
+        }
+    }
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_leaves() {
+        assert_eq!(
+            changes(
+                json!({ "api": { "port": "8080", "cors": true }, "old": 1 }),
+                json!({ "api": { "port": "9090", "cors": true }, "new": 2 })
+            ),
+            vec![
+                (
+                    "api.port".to_string(),
+                    Some(json!("8080")),
+                    Some(json!("9090"))
+                ),
+                ("new".to_string(), None, Some(json!(2))),
+                ("old".to_string(), Some(json!(1)), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_indexes_list_items() {
+        assert_eq!(
+            changes(
+                json!({ "llms": [{ "name": "a" }, { "name": "b" }] }),
+                json!({ "llms": [{ "name": "a2" }] })
+            ),
+            vec![
+                (
+                    "llms[0].name".to_string(),
+                    Some(json!("a")),
+                    Some(json!("a2"))
+                ),
+                ("llms[1]".to_string(), Some(json!({ "name": "b" })), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_of_equal_values_is_empty() {
+        let value = json!({ "a": [1, { "b": null }] });
+        assert!(changes(value.clone(), value).is_empty());
+    }
+
+    #[test]
+    fn diff_treats_type_changes_and_nulls_as_leaves() {
+        assert_eq!(
+            changes(json!({ "a": { "b": 1 } }), json!({ "a": "x" })),
+            vec![("a".to_string(), Some(json!({ "b": 1 })), Some(json!("x")))]
+        );
+        assert_eq!(
+            changes(json!({ "a": null }), json!({ "a": 1 })),
+            vec![("a".to_string(), None, Some(json!(1)))]
+        );
+    }
+
+    #[test]
+    fn launch_keys_match_whole_segments() {
+        let backend = ConfigFileKind::Backend;
+        assert!(affects_launch(backend, &[change("api.port")]));
+        assert!(affects_launch(backend, &[change("database.path")]));
+        assert!(affects_launch(backend, &[change("llms")]));
+        assert!(affects_launch(backend, &[change("llms[0].api_key")]));
+        assert!(!affects_launch(backend, &[change("api.port_alt")]));
+        assert!(!affects_launch(backend, &[change("api.rate_limit")]));
+        assert!(!affects_launch(backend, &[change("llms_extra")]));
+        assert!(!affects_launch(backend, &[]));
+    }
+
+    #[test]
+    fn launch_keys_depend_on_the_file() {
+        let desktop = ConfigFileKind::Desktop;
+        assert!(affects_launch(desktop, &[change("backend.port")]));
+        assert!(affects_launch(desktop, &[change("workspaces.active")]));
+        assert!(!affects_launch(desktop, &[change("api.port")]));
+        assert!(!affects_launch(
+            desktop,
+            &[change("tray.keep_running_on_close")]
+        ));
+        assert!(!affects_launch(
+            ConfigFileKind::Backend,
+            &[change("backend.port")]
+        ));
+    }
+
+    #[test]
+    fn creates_a_missing_directory_to_watch_it() {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-verifier-watcher-{}-missing",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let mut watcher = notify::recommended_watcher(|_| {}).unwrap();
+
+        let nested = dir.join("app").join("config");
+        watch_dir(&mut watcher, &nested).unwrap();
+        assert!(nested.is_dir());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn own_writes_match_exact_contents() {
+        let own = OwnWrites::default();
+        let path = Path::new("/config/config.json");
+        own.record(path, "{}");
+        assert!(own.contains(path, "{}"));
+        assert!(!own.contains(path, "{ }"));
+        assert!(!own.contains(Path::new("/other.json"), "{}"));
+        own.record(path, "{\"a\":1}");
+        assert!(!own.contains(path, "{}"));
+    }
+}
@@ -0,0 +1,252 @@
+//! Named workspaces, each pointing the managed backend at its own
+//! `config.yaml`, database, port and set of vault secrets.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::backend::launch::LaunchOptions;
+use crate::backend::profile::{ConnectionProfile, Profiles};
+use crate::backend::{BackendError, BackendProcess};
+use crate::config;
+
+/// Name of the workspace used when the desktop config defines none.
+const DEFAULT_WORKSPACE: &str = "default";
+
+/// Overrides applied on top of the `backend` section while a workspace is
+/// active. Unset fields keep the `backend` value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    pub config_path: Option<PathBuf>,
+    pub database_path: Option<PathBuf>,
+    pub port: Option<u16>,
+    /// Vault secrets passed to the backend; every secret when unset.
+    pub secrets: Option<BTreeSet<String>>,
+}
+
+impl Workspace {
+    pub fn apply(&self, mut options: LaunchOptions) -> LaunchOptions {
+        if let Some(config_path) = &self.config_path {
+            options.config_path = Some(config_path.clone());
+        }
+        if let Some(database_path) = &self.database_path {
+            options.database_path = Some(database_path.clone());
+        }
+        if let Some(port) = self.port {
+            options.port = Some(port);
+        }
+        options
+    }
+
+    /// Whether the vault secret `name` belongs to this workspace.
+    pub fn has_secret(&self, name: &str) -> bool {
+        self.secrets
+            .as_ref()
+            .map_or(true, |secrets| secrets.contains(name))
+    }
+}
+
+/// The `workspaces` section of the desktop config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceSettings {
+    pub active: String,
+    pub workspaces: BTreeMap<String, Workspace>,
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        let mut workspaces = BTreeMap::new();
+        workspaces.insert(DEFAULT_WORKSPACE.to_string(), Workspace::default());
+        Self {
+            active: DEFAULT_WORKSPACE.to_string(),
+            workspaces,
+        }
+    }
+}
+
+impl WorkspaceSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.workspaces.contains_key(&self.active) {
+            return Err(format!("Unknown workspace `{}`", self.active));
+        }
+        for (name, workspace) in &self.workspaces {
+            validate_name(name)?;
+            if workspace.port == Some(0) {
+                return Err(format!(
+                    "Workspace `{}`: port must be between 1 and 65535",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn active_workspace(&self) -> &Workspace {
+        static NONE: Workspace = Workspace {
+            config_path: None,
+            database_path: None,
+            port: None,
+            secrets: None,
+        };
+        self.workspaces.get(&self.active).unwrap_or(&NONE)
+    }
+}
+
+/// Workspace names double as cache keys, so they must be plain.
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Workspace name must not be empty".to_string());
+    }
+    if name.contains(|c: char| c == '/' || c == '\\' || c.is_control()) {
+        return Err(format!(
+            "Workspace name `{}` must not contain slashes or control characters",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Managed state holding the workspace settings.
+pub struct Workspaces {
+    settings: RwLock<WorkspaceSettings>,
+}
+
+impl Workspaces {
+    /// Loads the settings saved in the desktop config.
+    pub fn load(app: &AppHandle) -> Self {
+        let settings = config::load(app)
+            .map(|config| config.workspaces)
+            .map_err(|e| e.to_string())
+            .and_then(|settings| settings.validate().map(|_| settings))
+            .unwrap_or_else(|e| {
+                eprintln!("Using the default workspace: {}", e);
+                WorkspaceSettings::default()
+            });
+        Self {
+            settings: RwLock::new(settings),
+        }
+    }
+
+    pub async fn active_name(&self) -> String {
+        self.settings.read().await.active.clone()
+    }
+
+    pub async fn active(&self) -> Workspace {
+        self.settings.read().await.active_workspace().clone()
+    }
+
+    /// Adopts settings saved with the rest of the desktop config. Returns
+    /// whether the active workspace changed.
+    pub async fn reload(&self, settings: WorkspaceSettings) -> bool {
+        let mut current = self.settings.write().await;
+        let changed = current.active != settings.active;
+        *current = settings;
+        changed
+    }
+
+    /// Applies `change` to a copy of the settings, then saves and adopts it.
+    async fn update<F>(&self, app: &AppHandle, change: F) -> Result<WorkspaceSettings, String>
+    where
+        F: FnOnce(&mut WorkspaceSettings) -> Result<(), String>,
+    {
+        let mut settings = self.settings.write().await;
+        let mut updated = settings.clone();
+        change(&mut updated)?;
+        updated.validate()?;
+        config::update(app, |config| config.workspaces = updated.clone())
+            .map_err(|e| e.to_string())?;
+        *settings = updated.clone();
+        Ok(updated)
+    }
+}
+
+#[tauri::command]
+pub async fn list_workspaces(
+    workspaces: State<'_, Workspaces>,
+) -> Result<WorkspaceSettings, String> {
+    Ok(workspaces.settings.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn create_workspace(
+    app: AppHandle,
+    workspaces: State<'_, Workspaces>,
+    name: String,
+    workspace: Workspace,
+) -> Result<WorkspaceSettings, String> {
+    workspaces
+        .update(&app, |settings| {
+            validate_name(&name)?;
+            if settings.workspaces.contains_key(&name) {
+                return Err(format!("Workspace `{}` already exists", name));
+            }
+            settings.workspaces.insert(name.clone(), workspace);
+            Ok(())
+        })
+        .await
+}
+
+/// Copies the settings of workspace `from` under a new name.
+#[tauri::command]
+pub async fn clone_workspace(
+    app: AppHandle,
+    workspaces: State<'_, Workspaces>,
+    from: String,
+    name: String,
+) -> Result<WorkspaceSettings, String> {
+    workspaces
+        .update(&app, |settings| {
+            validate_name(&name)?;
+            let source = settings
+                .workspaces
+                .get(&from)
+                .cloned()
+                .ok_or_else(|| format!("Unknown workspace `{}`", from))?;
+            if settings.workspaces.contains_key(&name) {
+                return Err(format!("Workspace `{}` already exists", name));
+            }
+            settings.workspaces.insert(name.clone(), source);
+            Ok(())
+        })
+        .await
+}
+
+/// Makes `name` the active workspace. With the managed profile, the running
+/// backend is stopped and one bound to the new workspace is started.
+#[tauri::command]
+pub async fn switch_workspace(
+    app: AppHandle,
+    workspaces: State<'_, Workspaces>,
+    profiles: State<'_, Profiles>,
+    backend: State<'_, BackendProcess>,
+    name: String,
+) -> Result<String, String> {
+    workspaces
+        .update(&app, |settings| {
+            if !settings.workspaces.contains_key(&name) {
+                return Err(format!("Unknown workspace `{}`", name));
+            }
+            settings.active = name.clone();
+            Ok(())
+        })
+        .await?;
+    app.emit_all("workspace://changed", &name)
+        .map_err(|e| e.to_string())?;
+
+    if profiles.active().await != ConnectionProfile::Managed {
+        return Ok(format!("Using workspace `{}`", name));
+    }
+    match backend.stop().await {
+        Ok(_) | Err(BackendError::NotRunning) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+    let started = crate::launch_managed(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(format!("Using workspace `{}`. {}", name, started))
+}
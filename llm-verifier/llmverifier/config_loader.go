@@ -65,6 +65,8 @@ func LoadConfigWithProfile(filePath, profile string) (*config.Config, error) {
 func setupViper(filePath, profile string) error {
 	viper.AutomaticEnv() // Allow environment variables to override config
 	viper.SetEnvPrefix("LLM_VERIFIER")
+	// Nested keys use underscores, e.g. LLM_VERIFIER_DATABASE_PATH for database.path
+	viper.SetEnvKeyReplacer(strings.NewReplacer(".", "_"))
 
 	// Set config file
 	if profile != "" {
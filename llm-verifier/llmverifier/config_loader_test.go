@@ -195,6 +195,35 @@ global:
 	}
 }
 
+func TestLoadConfig_EnvironmentOverridesNestedKeys(t *testing.T) {
+	tempDir := t.TempDir()
+	configPath := filepath.Join(tempDir, "test_env_override_config.yaml")
+	configContent := `
+llms:
+  - name: "test-llm"
+    endpoint: "https://api.test.com/v1"
+    api_key: "test-api-key"
+
+database:
+  path: "from_file.db"
+`
+
+	if err := os.WriteFile(configPath, []byte(configContent), 0644); err != nil {
+		t.Fatalf("Failed to create test config file: %v", err)
+	}
+
+	t.Setenv("LLM_VERIFIER_DATABASE_PATH", filepath.Join(tempDir, "from_env.db"))
+
+	cfg, err := LoadConfig(configPath)
+	if err != nil {
+		t.Fatalf("LoadConfig failed: %v", err)
+	}
+
+	if cfg.Database.Path != filepath.Join(tempDir, "from_env.db") {
+		t.Errorf("Expected database path from LLM_VERIFIER_DATABASE_PATH, got '%s'", cfg.Database.Path)
+	}
+}
+
 func TestLoadConfig_Defaults(t *testing.T) {
 	// Create a minimal config file
 	tempDir := t.TempDir()
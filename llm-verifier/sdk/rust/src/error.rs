@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors returned by [`Client`](crate::Client).
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid base URL `{0}`")]
+    InvalidBaseUrl(String),
+    /// The request never got a response: DNS, connect, TLS or timeout failures.
+    #[error("request to {url} failed: {source}")]
+    Transport {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    /// The server answered with a non-2xx status.
+    #[error("API error ({status}) from {url}: {body}")]
+    Status {
+        url: String,
+        status: StatusCode,
+        body: String,
+    },
+    /// The response body did not match the expected type.
+    #[error("failed to decode response from {url}: {source}")]
+    Decode {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The stored JWT expired; call [`Client::login`](crate::Client::login) again.
+    #[error("session token expired at {0}")]
+    TokenExpired(DateTime<Utc>),
+    /// The server has no login endpoint, so it cannot issue tokens.
+    #[error("the server has no login endpoint")]
+    LoginUnsupported,
+}
+
+impl Error {
+    /// HTTP status of a [`Error::Status`] failure.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
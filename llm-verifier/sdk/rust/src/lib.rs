@@ -0,0 +1,495 @@
+//! Rust client for the LLM Verifier REST API.
+//!
+//! Mirrors `sdk/go/client.go`: [`Client::login`], [`Client::get_models`],
+//! [`Client::get_model`], [`Client::verify_model`],
+//! [`Client::get_verification_results`], [`Client::get_providers`],
+//! [`Client::get_health`] and [`Client::get_system_info`].
+//!
+//! The model and provider lists can also be fetched conditionally with
+//! [`Client::list_models_if_modified`] and
+//! [`Client::list_providers_if_modified`], for callers that cache them.
+//!
+//! ```no_run
+//! # async fn run() -> llm_verifier_client::Result<()> {
+//! use llm_verifier_client::Client;
+//!
+//! let client = Client::new("http://localhost:8080")?;
+//! let auth = client.login("admin", "password").await?;
+//! println!("Logged in as: {}", auth.user.username);
+//!
+//! let models = client.get_models(10, 0, None).await?;
+//! println!("Found {} models", models.len());
+//! # Ok(())
+//! # }
+//! ```
+
+mod conditional;
+mod error;
+mod pagination;
+mod token;
+pub mod types;
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub use conditional::{Conditional, Validators};
+pub use error::{Error, Result};
+pub use pagination::{Paged, Pages};
+pub use token::Token;
+use types::{
+    AuthResponse, HealthStatus, Model, ModelDetails, ModelQuery, Provider, ProviderQuery,
+    SystemInfo, Verification, VerificationResult,
+};
+
+/// Default timeout of a single request, as in the Go SDK.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the API lives on a server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Routes {
+    /// Prefix of the model, provider, verification and system routes.
+    pub api: &'static str,
+    /// `None` when the server has no login endpoint.
+    pub login: Option<&'static str>,
+    pub health: &'static str,
+}
+
+impl Routes {
+    /// The documented `/api/v1` API, as used by the Go, Python and JavaScript SDKs.
+    pub const V1: Self = Self {
+        api: "/api/v1",
+        login: Some("/auth/login"),
+        health: "/health",
+    };
+
+    /// The routes registered by `llm-verifier server` in `api/server.go`,
+    /// which has no authentication.
+    pub const SERVER: Self = Self {
+        api: "/api",
+        login: None,
+        health: "/api/health",
+    };
+}
+
+impl Default for Routes {
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Auth {
+    None,
+    Bearer(Token),
+    Basic { username: String, password: String },
+}
+
+pub struct ClientBuilder {
+    base_url: String,
+    routes: Routes,
+    timeout: Duration,
+    http: Option<reqwest::Client>,
+    auth: Auth,
+}
+
+impl ClientBuilder {
+    pub fn routes(mut self, routes: Routes) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Ignored when [`ClientBuilder::http_client`] is set.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Shares an existing connection pool instead of creating one.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Starts with a token from an earlier login, like the Go SDK's `apiKey`.
+    pub fn token(mut self, token: Token) -> Self {
+        self.auth = Auth::Bearer(token);
+        self
+    }
+
+    pub fn basic_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.auth = Auth::Basic {
+            username: username.into(),
+            password: password.into(),
+        };
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let url = reqwest::Url::parse(&self.base_url)
+            .map_err(|_| Error::InvalidBaseUrl(self.base_url.clone()))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(Error::InvalidBaseUrl(self.base_url));
+        }
+
+        let http = match self.http {
+            Some(http) => http,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout)
+                .build()
+                .map_err(|source| Error::Transport {
+                    url: self.base_url.clone(),
+                    source,
+                })?,
+        };
+        Ok(Client {
+            http,
+            base_url: self.base_url.trim_end_matches('/').to_string(),
+            routes: self.routes,
+            auth: RwLock::new(self.auth),
+        })
+    }
+}
+
+/// Client for one LLM Verifier server.
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    routes: Routes,
+    auth: RwLock<Auth>,
+}
+
+impl Client {
+    /// Client for `base_url` using the [`Routes::V1`] layout.
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        Self::builder(base_url).build()
+    }
+
+    pub fn builder(base_url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            base_url: base_url.into(),
+            routes: Routes::default(),
+            timeout: DEFAULT_TIMEOUT,
+            http: None,
+            auth: Auth::None,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Authenticates and uses the returned JWT for all further requests.
+    /// Fails with [`Error::LoginUnsupported`] without sending anything when
+    /// the routes have no login endpoint.
+    pub async fn login(&self, username: &str, password: &str) -> Result<AuthResponse> {
+        let route = self.routes.login.ok_or(Error::LoginUnsupported)?;
+        let body = serde_json::json!({ "username": username, "password": password });
+        let auth: AuthResponse = self
+            .request(Method::POST, route, &[], Some(&body), false)
+            .await?;
+        let token = Token::new(auth.token.clone()).or_expires_at(&auth.expires_at);
+        *self.auth.write().unwrap() = Auth::Bearer(token);
+        Ok(auth)
+    }
+
+    /// The bearer token in use, if logged in.
+    pub fn token(&self) -> Option<Token> {
+        match &*self.auth.read().unwrap() {
+            Auth::Bearer(token) => Some(token.clone()),
+            _ => None,
+        }
+    }
+
+    /// Forgets the bearer token; later requests are sent unauthenticated.
+    pub fn logout(&self) {
+        *self.auth.write().unwrap() = Auth::None;
+    }
+
+    /// Lists models. `limit` and `offset` of 0 and a `None` provider are not
+    /// sent, as in the Go SDK.
+    pub async fn get_models(
+        &self,
+        limit: u32,
+        offset: u32,
+        provider: Option<&str>,
+    ) -> Result<Vec<Model>> {
+        self.list_models(&ModelQuery {
+            limit: Some(limit).filter(|&l| l > 0),
+            offset: Some(offset).filter(|&o| o > 0),
+            provider: provider.map(str::to_string),
+            ..ModelQuery::default()
+        })
+        .await
+    }
+
+    pub async fn list_models(&self, query: &ModelQuery) -> Result<Vec<Model>> {
+        self.list(&self.api_path("/models"), &query_pairs(query))
+            .await
+    }
+
+    /// Lists models unless they are unchanged since the response `validators`
+    /// came from.
+    pub async fn list_models_if_modified(
+        &self,
+        query: &ModelQuery,
+        validators: &Validators,
+    ) -> Result<Conditional<Vec<Model>>> {
+        let path = self.api_path("/models");
+        self.list_if_modified(&path, &query_pairs(query), "models", validators)
+            .await
+    }
+
+    /// Pages through the models matching `query`; its `limit` and `offset`
+    /// are replaced by the pager's.
+    pub fn models(&self, query: &ModelQuery, page_size: u32) -> Pages<'_, Model> {
+        let query = ModelQuery {
+            limit: None,
+            offset: None,
+            ..query.clone()
+        };
+        Pages::new(
+            self,
+            self.api_path("/models"),
+            query_pairs(&query),
+            page_size,
+        )
+    }
+
+    pub async fn get_model(&self, id: i64) -> Result<ModelDetails> {
+        let path = self.api_path(&format!("/models/{}", id));
+        self.request(Method::GET, &path, &[], None, true).await
+    }
+
+    /// Starts a verification run for the model.
+    pub async fn verify_model(&self, id: i64) -> Result<Verification> {
+        let path = self.api_path(&format!("/models/{}/verify", id));
+        let body = serde_json::json!({ "model_id": id });
+        self.request(Method::POST, &path, &[], Some(&body), true)
+            .await
+    }
+
+    pub async fn get_verification_results(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<VerificationResult>> {
+        let mut query = Vec::new();
+        if limit > 0 {
+            query.push(("limit".to_string(), limit.to_string()));
+        }
+        if offset > 0 {
+            query.push(("offset".to_string(), offset.to_string()));
+        }
+        self.list(&self.api_path("/verification-results"), &query)
+            .await
+    }
+
+    pub fn verification_results(&self, page_size: u32) -> Pages<'_, VerificationResult> {
+        Pages::new(
+            self,
+            self.api_path("/verification-results"),
+            Vec::new(),
+            page_size,
+        )
+    }
+
+    pub async fn get_providers(&self) -> Result<Vec<Provider>> {
+        self.list_providers(&ProviderQuery::default()).await
+    }
+
+    pub async fn list_providers(&self, query: &ProviderQuery) -> Result<Vec<Provider>> {
+        let path = self.api_path("/providers");
+        let body: serde_json::Value = self
+            .request(Method::GET, &path, &query_pairs(query), None, true)
+            .await?;
+        list_items(&self.url(&path), body, "providers")
+    }
+
+    pub async fn list_providers_if_modified(
+        &self,
+        query: &ProviderQuery,
+        validators: &Validators,
+    ) -> Result<Conditional<Vec<Provider>>> {
+        let path = self.api_path("/providers");
+        self.list_if_modified(&path, &query_pairs(query), "providers", validators)
+            .await
+    }
+
+    pub async fn get_health(&self) -> Result<HealthStatus> {
+        self.request(Method::GET, self.routes.health, &[], None, true)
+            .await
+    }
+
+    pub async fn get_system_info(&self) -> Result<SystemInfo> {
+        let path = self.api_path("/system/info");
+        self.request(Method::GET, &path, &[], None, true).await
+    }
+
+    fn api_path(&self, path: &str) -> String {
+        format!("{}{}", self.routes.api, path)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub(crate) async fn list<T: Paged>(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+    ) -> Result<Vec<T>> {
+        let body: serde_json::Value = self.request(Method::GET, path, query, None, true).await?;
+        list_items(&self.url(path), body, T::LIST_KEY)
+    }
+
+    async fn list_if_modified<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+        key: &str,
+        validators: &Validators,
+    ) -> Result<Conditional<Vec<T>>> {
+        let url = self.url(path);
+        match self
+            .send(Method::GET, path, query, None, true, validators)
+            .await?
+        {
+            Conditional::Modified { value, validators } => {
+                let body = serde_json::from_str(&value).map_err(|source| Error::Decode {
+                    url: url.clone(),
+                    source,
+                })?;
+                Ok(Conditional::Modified {
+                    value: list_items(&url, body, key)?,
+                    validators,
+                })
+            }
+            Conditional::NotModified => Ok(Conditional::NotModified),
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(String, String)],
+        body: Option<&serde_json::Value>,
+        authenticated: bool,
+    ) -> Result<T> {
+        let url = self.url(path);
+        let text = match self
+            .send(
+                method,
+                path,
+                query,
+                body,
+                authenticated,
+                &Validators::default(),
+            )
+            .await?
+        {
+            Conditional::Modified { value, .. } => value,
+            Conditional::NotModified => unreachable!("304 without validators is a status error"),
+        };
+        serde_json::from_str(&text).map_err(|source| Error::Decode { url, source })
+    }
+
+    /// Sends a request and returns the body of a 2xx response. A 304 counts
+    /// as success only when `validators` were sent.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(String, String)],
+        body: Option<&serde_json::Value>,
+        authenticated: bool,
+        validators: &Validators,
+    ) -> Result<Conditional<String>> {
+        let url = self.url(path);
+        let mut request = self.http.request(method, &url).query(query);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        if authenticated {
+            request = self.authorize(request)?;
+        }
+        request = validators.apply(request);
+
+        let response = request.send().await.map_err(|source| Error::Transport {
+            url: url.clone(),
+            source,
+        })?;
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED && !validators.is_empty() {
+            return Ok(Conditional::NotModified);
+        }
+        let received = Validators::from_headers(response.headers());
+        let text = response.text().await.map_err(|source| Error::Transport {
+            url: url.clone(),
+            source,
+        })?;
+        if !status.is_success() {
+            return Err(Error::Status {
+                url,
+                status,
+                // `http.Error` bodies end with a newline.
+                body: text.trim_end().to_string(),
+            });
+        }
+        Ok(Conditional::Modified {
+            value: text,
+            validators: received,
+        })
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+        match &*self.auth.read().unwrap() {
+            Auth::None => Ok(request),
+            Auth::Bearer(token) => match token.expires_at {
+                Some(at) if token.is_expired() => Err(Error::TokenExpired(at)),
+                _ => Ok(request.bearer_auth(&token.value)),
+            },
+            Auth::Basic { username, password } => Ok(request.basic_auth(username, Some(password))),
+        }
+    }
+}
+
+/// Accepts both a bare array (`/api/v1`) and `{"<key>": [...], "count": n}`
+/// (`llm-verifier server`).
+fn list_items<T: DeserializeOwned>(
+    url: &str,
+    body: serde_json::Value,
+    key: &str,
+) -> Result<Vec<T>> {
+    let items = match body {
+        serde_json::Value::Object(mut object) => object.remove(key).unwrap_or_default(),
+        other => other,
+    };
+    if items.is_null() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_value(items).map_err(|source| Error::Decode {
+        url: url.to_string(),
+        source,
+    })
+}
+
+/// Flattens a query struct into `key=value` pairs, skipping unset fields.
+fn query_pairs<Q: Serialize>(query: &Q) -> Vec<(String, String)> {
+    let object = match serde_json::to_value(query) {
+        Ok(serde_json::Value::Object(object)) => object,
+        _ => return Vec::new(),
+    };
+    object
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => (key, s),
+            other => (key, other.to_string()),
+        })
+        .collect()
+}
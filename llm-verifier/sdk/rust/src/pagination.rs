@@ -0,0 +1,106 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::types::{Model, VerificationResult};
+use crate::{Client, Result};
+
+/// Items that can be listed page by page.
+pub trait Paged: DeserializeOwned {
+    /// Key the items are wrapped under in `{"<key>": [...], "count": n}` bodies.
+    const LIST_KEY: &'static str;
+
+    fn id(&self) -> i64;
+}
+
+impl Paged for Model {
+    const LIST_KEY: &'static str = "models";
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+impl Paged for VerificationResult {
+    const LIST_KEY: &'static str = "results";
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+/// Walks a list endpoint with `limit`/`offset`, one page per call to
+/// [`Pages::next_page`].
+///
+/// Iteration ends on a short or empty page. It also ends when a page starts
+/// with the same item as the previous one, which is how a server that ignores
+/// `offset` (such as the `llm-verifier server` routes) shows up.
+pub struct Pages<'a, T> {
+    client: &'a Client,
+    path: String,
+    query: Vec<(String, String)>,
+    page_size: u32,
+    offset: u32,
+    previous_first: Option<i64>,
+    done: bool,
+    _item: PhantomData<T>,
+}
+
+impl<'a, T: Paged> Pages<'a, T> {
+    pub(crate) fn new(
+        client: &'a Client,
+        path: String,
+        query: Vec<(String, String)>,
+        page_size: u32,
+    ) -> Self {
+        Self {
+            client,
+            path,
+            query,
+            page_size: page_size.max(1),
+            offset: 0,
+            previous_first: None,
+            done: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Fetches the next page, or returns `None` once the list is exhausted.
+    pub async fn next_page(&mut self) -> Option<Result<Vec<T>>> {
+        if self.done {
+            return None;
+        }
+
+        let mut query = self.query.clone();
+        query.push(("limit".to_string(), self.page_size.to_string()));
+        query.push(("offset".to_string(), self.offset.to_string()));
+        let items = match self.client.list::<T>(&self.path, &query).await {
+            Ok(items) => items,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+
+        let first = items.first().map(Paged::id);
+        if first.is_none() || first == self.previous_first {
+            self.done = true;
+            return None;
+        }
+        if items.len() < self.page_size as usize {
+            self.done = true;
+        }
+        self.previous_first = first;
+        self.offset += items.len() as u32;
+        Some(Ok(items))
+    }
+
+    /// Fetches every remaining page.
+    pub async fn try_collect(mut self) -> Result<Vec<T>> {
+        let mut all = Vec::new();
+        while let Some(page) = self.next_page().await {
+            all.extend(page?);
+        }
+        Ok(all)
+    }
+}
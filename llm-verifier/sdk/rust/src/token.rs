@@ -0,0 +1,46 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A bearer token and, for JWTs, when it stops being accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    pub value: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Token {
+    /// Wraps `value`, reading the expiry from its `exp` claim if it is a JWT.
+    /// The signature is not checked; that is the server's job.
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let expires_at = jwt_expiry(&value);
+        Self { value, expires_at }
+    }
+
+    /// Uses `expires_at` when the token itself carries no expiry.
+    pub(crate) fn or_expires_at(mut self, expires_at: &str) -> Self {
+        if self.expires_at.is_none() {
+            self.expires_at = DateTime::parse_from_rfc3339(expires_at)
+                .ok()
+                .map(|at| at.with_timezone(&Utc));
+        }
+        self
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map_or(false, |at| at <= Utc::now())
+    }
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    exp: Option<i64>,
+}
+
+fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let payload = token.split('.').nth(1)?;
+    let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    Utc.timestamp_opt(claims.exp?, 0).single()
+}
@@ -0,0 +1,269 @@
+//! Request and response bodies.
+//!
+//! Field names follow `api/handlers.go`; aliases accept the names used by the
+//! `/api/v1` layout and the other SDKs (`overall_score`, `verification_status`).
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Go encodes nil slices as `null`.
+fn null_as_empty<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// `HealthHandler` sends a Unix timestamp, the `/health` route an RFC 3339 time.
+fn unix_or_rfc3339<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Unix(i64),
+        Text(DateTime<Utc>),
+    }
+
+    match Timestamp::deserialize(deserializer)? {
+        Timestamp::Unix(secs) => Utc
+            .timestamp_opt(secs, 0)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom("timestamp out of range")),
+        Timestamp::Text(at) => Ok(at),
+    }
+}
+
+/// Body of a successful login.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthResponse {
+    pub token: String,
+    #[serde(default)]
+    pub expires_at: String,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub role: String,
+}
+
+/// A model as returned by the model list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Model {
+    pub id: i64,
+    pub model_id: String,
+    pub name: String,
+    /// Provider name; empty when the provider no longer exists.
+    #[serde(default)]
+    pub provider: String,
+    pub provider_id: i64,
+    #[serde(alias = "verification_status")]
+    pub status: String,
+    #[serde(default, alias = "overall_score")]
+    pub score: f64,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub capabilities: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub deprecated: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A single model with its full capability and score breakdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDetails {
+    #[serde(flatten)]
+    pub model: Model,
+    #[serde(default)]
+    pub architecture: String,
+    /// Human-readable parameter count, e.g. `"7.0 billion"`.
+    #[serde(default)]
+    pub parameters: String,
+    /// Human-readable context window, e.g. `"128K tokens"`.
+    #[serde(default)]
+    pub context: String,
+    #[serde(default)]
+    pub context_window_tokens: Option<i64>,
+    #[serde(default)]
+    pub max_output_tokens: Option<i64>,
+    #[serde(default)]
+    pub is_multimodal: bool,
+    #[serde(default)]
+    pub supports_vision: bool,
+    #[serde(default)]
+    pub supports_audio: bool,
+    #[serde(default)]
+    pub supports_video: bool,
+    #[serde(default)]
+    pub supports_reasoning: bool,
+    #[serde(default)]
+    pub open_source: bool,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub use_case: String,
+    #[serde(default)]
+    pub code_capability_score: f64,
+    #[serde(default)]
+    pub responsiveness_score: f64,
+    #[serde(default)]
+    pub reliability_score: f64,
+    #[serde(default)]
+    pub feature_richness_score: f64,
+    #[serde(default)]
+    pub last_verified: Option<DateTime<Utc>>,
+}
+
+/// Answer to a verification request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verification {
+    pub status: String,
+    pub model_id: i64,
+    #[serde(default)]
+    pub model_name: String,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub job_id: i64,
+    #[serde(alias = "id")]
+    pub verification_id: i64,
+    pub started_at: DateTime<Utc>,
+}
+
+/// A stored verification run and its scores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationResult {
+    pub id: i64,
+    pub model_id: i64,
+    pub status: String,
+    #[serde(default, alias = "overall_score")]
+    pub score: f64,
+    #[serde(default)]
+    pub code_capability_score: f64,
+    #[serde(default)]
+    pub responsiveness_score: f64,
+    #[serde(default)]
+    pub reliability_score: f64,
+    #[serde(default)]
+    pub feature_richness_score: f64,
+    #[serde(default)]
+    pub value_proposition_score: f64,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provider {
+    pub id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub description: String,
+    /// `"active"` or `"inactive"`.
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub is_active: bool,
+    /// Number of models registered for the provider.
+    #[serde(default)]
+    pub models: usize,
+    #[serde(default)]
+    pub website: String,
+    #[serde(default)]
+    pub support_email: String,
+    #[serde(default)]
+    pub documentation_url: String,
+    #[serde(default)]
+    pub reliability_score: f64,
+    #[serde(default)]
+    pub average_response_time_ms: i64,
+    #[serde(default)]
+    pub last_checked: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub status: String,
+    #[serde(deserialize_with = "unix_or_rfc3339")]
+    pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub uptime: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub services: BTreeMap<String, String>,
+}
+
+impl HealthStatus {
+    pub fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub go_version: String,
+    #[serde(default)]
+    pub build_time: String,
+    #[serde(default)]
+    pub database_size: i64,
+    #[serde(default)]
+    pub models_count: i64,
+    #[serde(default)]
+    pub providers_count: i64,
+    #[serde(default)]
+    pub uptime: String,
+}
+
+/// Filters for the model list. Unset fields are not sent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    /// Provider name (`/api/v1` layout).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+}
+
+/// Filters for the provider list. Unset fields are not sent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+}
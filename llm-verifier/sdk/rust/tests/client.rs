@@ -0,0 +1,470 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use llm_verifier_client::{Client, Conditional, Error, Routes, Token, Validators};
+use serde_json::json;
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn model(id: i64) -> serde_json::Value {
+    json!({
+        "id": id,
+        "model_id": format!("model-{}", id),
+        "name": format!("Model {}", id),
+        "provider": "openai",
+        "provider_id": 1,
+        "status": "verified",
+        "score": 87.5,
+        "capabilities": ["text"],
+        "description": "",
+        "version": "1",
+        "deprecated": false,
+        "created_at": "2024-01-01T00:00:00Z",
+        "updated_at": "2024-01-02T00:00:00Z"
+    })
+}
+
+fn jwt(exp: i64) -> String {
+    let claims = URL_SAFE_NO_PAD.encode(json!({ "sub": "admin", "exp": exp }).to_string());
+    format!("eyJhbGciOiJIUzI1NiJ9.{}.signature", claims)
+}
+
+fn server_client(server: &MockServer) -> Client {
+    Client::builder(server.uri())
+        .routes(Routes::SERVER)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_new_rejects_invalid_base_url() {
+    assert!(matches!(
+        Client::new("localhost:8080"),
+        Err(Error::InvalidBaseUrl(_))
+    ));
+    assert!(matches!(Client::new(""), Err(Error::InvalidBaseUrl(_))));
+}
+
+#[tokio::test]
+async fn test_login_stores_token_for_later_requests() {
+    let server = MockServer::start().await;
+    let token = jwt(4_102_444_800); // 2100-01-01
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .and(body_json(
+            json!({ "username": "admin", "password": "secret" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "token": token,
+            "expires_at": "2100-01-01T00:00:00Z",
+            "user": { "id": 1, "username": "admin", "email": "admin@example.com", "role": "admin" }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/providers"))
+        .and(header(
+            "authorization",
+            format!("Bearer {}", token).as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new(server.uri()).unwrap();
+    let auth = client.login("admin", "secret").await.unwrap();
+    assert_eq!(auth.user.username, "admin");
+
+    let stored = client.token().unwrap();
+    assert_eq!(stored.value, token);
+    assert_eq!(stored.expires_at.unwrap().timestamp(), 4_102_444_800);
+    assert!(client.get_providers().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_login_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Invalid credentials\n"))
+        .mount(&server)
+        .await;
+
+    let client = Client::new(server.uri()).unwrap();
+    match client.login("admin", "wrong").await {
+        Err(Error::Status { status, body, .. }) => {
+            assert_eq!(status.as_u16(), 401);
+            assert_eq!(body, "Invalid credentials");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(client.token().is_none());
+}
+
+#[tokio::test]
+async fn test_login_without_login_route_sends_nothing() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = server_client(&server);
+    assert!(matches!(
+        client.login("admin", "secret").await,
+        Err(Error::LoginUnsupported)
+    ));
+    assert!(client.token().is_none());
+}
+
+#[tokio::test]
+async fn test_expired_token_is_not_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = Client::builder(server.uri())
+        .token(Token::new(jwt(946_684_800))) // 2000-01-01
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.get_providers().await,
+        Err(Error::TokenExpired(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_get_models_with_params() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/models"))
+        .and(query_param("limit", "10"))
+        .and(query_param("offset", "20"))
+        .and(query_param("provider", "openai"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([model(1), model(2)])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new(server.uri()).unwrap();
+    let models = client.get_models(10, 20, Some("openai")).await.unwrap();
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[1].model_id, "model-2");
+}
+
+#[tokio::test]
+async fn test_list_models_wrapped_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [model(1)],
+            "count": 1
+        })))
+        .mount(&server)
+        .await;
+
+    let models = server_client(&server).get_models(0, 0, None).await.unwrap();
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].score, 87.5);
+}
+
+#[tokio::test]
+async fn test_get_model() {
+    let server = MockServer::start().await;
+    let mut body = model(7);
+    body["architecture"] = json!("transformer");
+    body["context_window_tokens"] = json!(128000);
+    body["tags"] = json!(null);
+    Mock::given(method("GET"))
+        .and(path("/api/models/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(&server)
+        .await;
+
+    let details = server_client(&server).get_model(7).await.unwrap();
+    assert_eq!(details.model.id, 7);
+    assert_eq!(details.architecture, "transformer");
+    assert_eq!(details.context_window_tokens, Some(128000));
+    assert!(details.tags.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_model_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/models/99"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("404 page not found\n"))
+        .mount(&server)
+        .await;
+
+    let err = server_client(&server).get_model(99).await.unwrap_err();
+    assert_eq!(err.status().map(|s| s.as_u16()), Some(404));
+}
+
+#[tokio::test]
+async fn test_verify_model() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/models/3/verify"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "verification_started",
+            "model_id": 3,
+            "model_name": "Model 3",
+            "message": "Verification process initiated",
+            "job_id": 12,
+            "verification_id": 12,
+            "started_at": "2024-01-01T10:00:00.123456789Z"
+        })))
+        .mount(&server)
+        .await;
+
+    let verification = server_client(&server).verify_model(3).await.unwrap();
+    assert_eq!(verification.status, "verification_started");
+    assert_eq!(verification.verification_id, 12);
+}
+
+#[tokio::test]
+async fn test_get_verification_results() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/verification-results"))
+        .and(query_param("limit", "5"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "id": 1,
+            "model_id": 3,
+            "status": "completed",
+            "overall_score": 91.0,
+            "started_at": "2024-01-01T10:00:00Z",
+            "completed_at": null
+        }])))
+        .mount(&server)
+        .await;
+
+    let client = Client::new(server.uri()).unwrap();
+    let results = client.get_verification_results(5, 0).await.unwrap();
+    assert_eq!(results[0].score, 91.0);
+    assert!(results[0].completed_at.is_none());
+}
+
+#[tokio::test]
+async fn test_get_providers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/providers"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "providers": [{
+                "id": 1,
+                "name": "OpenAI",
+                "status": "active",
+                "is_active": true,
+                "models": 4,
+                "endpoint": "https://api.openai.com/v1",
+                "last_checked": null,
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z"
+            }],
+            "count": 1
+        })))
+        .mount(&server)
+        .await;
+
+    let providers = server_client(&server).get_providers().await.unwrap();
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0].models, 4);
+}
+
+#[tokio::test]
+async fn test_get_health() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/health"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "status": "healthy", "timestamp": 1_704_067_200 })),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "degraded",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "uptime": "1h",
+            "version": "1.0.0"
+        })))
+        .mount(&server)
+        .await;
+
+    let health = server_client(&server).get_health().await.unwrap();
+    assert!(health.is_healthy());
+    assert_eq!(health.timestamp.timestamp(), 1_704_067_200);
+
+    let health = Client::new(server.uri())
+        .unwrap()
+        .get_health()
+        .await
+        .unwrap();
+    assert!(!health.is_healthy());
+    assert_eq!(health.timestamp.timestamp(), 1_704_067_200);
+}
+
+#[tokio::test]
+async fn test_get_system_info() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/system/info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "version": "1.0.0",
+            "go_version": "go1.21",
+            "models_count": 12,
+            "providers_count": 3
+        })))
+        .mount(&server)
+        .await;
+
+    let info = Client::new(server.uri())
+        .unwrap()
+        .get_system_info()
+        .await
+        .unwrap();
+    assert_eq!(info.go_version, "go1.21");
+    assert_eq!(info.models_count, 12);
+}
+
+#[tokio::test]
+async fn test_decode_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/models/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+        .mount(&server)
+        .await;
+
+    let client = Client::new(server.uri()).unwrap();
+    assert!(matches!(
+        client.get_model(1).await,
+        Err(Error::Decode { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_models_pagination() {
+    let server = MockServer::start().await;
+    for (offset, ids) in [(0, vec![1, 2]), (2, vec![3, 4]), (4, vec![5])] {
+        let page: Vec<_> = ids.into_iter().map(model).collect();
+        Mock::given(method("GET"))
+            .and(path("/api/v1/models"))
+            .and(query_param("limit", "2"))
+            .and(query_param("offset", offset.to_string().as_str()))
+            .and(query_param("status", "verified"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = Client::new(server.uri()).unwrap();
+    let query = llm_verifier_client::types::ModelQuery {
+        status: Some("verified".to_string()),
+        ..Default::default()
+    };
+    let mut pages = client.models(&query, 2);
+    let mut sizes = Vec::new();
+    while let Some(page) = pages.next_page().await {
+        sizes.push(page.unwrap().len());
+    }
+    assert_eq!(sizes, vec![2, 2, 1]);
+}
+
+#[tokio::test]
+async fn test_pagination_stops_when_offset_is_ignored() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [model(1), model(2)],
+            "count": 2
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = server_client(&server);
+    let models = client
+        .models(&Default::default(), 2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(models.len(), 2);
+}
+
+#[tokio::test]
+async fn test_list_models_if_modified() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/models"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/models"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .insert_header("last-modified", "Mon, 01 Jan 2024 00:00:00 GMT")
+                .set_body_json(json!({ "models": [model(1)], "count": 1 })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = server_client(&server);
+    let validators = match client
+        .list_models_if_modified(&Default::default(), &Validators::default())
+        .await
+        .unwrap()
+    {
+        Conditional::Modified { value, validators } => {
+            assert_eq!(value.len(), 1);
+            validators
+        }
+        Conditional::NotModified => panic!("expected a body"),
+    };
+    assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+    assert_eq!(
+        validators.last_modified.as_deref(),
+        Some("Mon, 01 Jan 2024 00:00:00 GMT")
+    );
+
+    assert!(matches!(
+        client
+            .list_models_if_modified(&Default::default(), &validators)
+            .await
+            .unwrap(),
+        Conditional::NotModified
+    ));
+}
+
+#[tokio::test]
+async fn test_not_modified_without_validators_is_an_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/providers"))
+        .respond_with(ResponseTemplate::new(304))
+        .mount(&server)
+        .await;
+
+    let err = server_client(&server)
+        .list_providers_if_modified(&Default::default(), &Validators::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.status().map(|s| s.as_u16()), Some(304));
+}